@@ -0,0 +1,43 @@
+// Conversions for GUIs built on embedded-graphics (enabled with the embedded-graphics feature)
+
+use crate::{Point, TouchEvent, TransformedPoint};
+use embedded_graphics_core::geometry;
+
+impl From<&Point> for geometry::Point {
+    fn from(point: &Point) -> Self {
+        geometry::Point::new(point.x as i32, point.y as i32)
+    }
+}
+
+impl From<Point> for geometry::Point {
+    fn from(point: Point) -> Self {
+        (&point).into()
+    }
+}
+
+impl From<&TransformedPoint> for geometry::Point {
+    fn from(point: &TransformedPoint) -> Self {
+        geometry::Point::new(point.x as i32, point.y as i32)
+    }
+}
+
+impl From<TransformedPoint> for geometry::Point {
+    fn from(point: TransformedPoint) -> Self {
+        (&point).into()
+    }
+}
+
+impl TransformedPoint {
+    /// The point in embedded-graphics coordinates, passed through unchanged (every i16 fits in the i32 coordinates)
+    /// Negative coordinates (off the top or left edge after an offset) are kept so drawing code can clip them
+    pub fn as_eg_point(&self) -> geometry::Point {
+        self.into()
+    }
+}
+
+impl TouchEvent {
+    /// The position of the event in embedded-graphics screen coordinates
+    pub fn position(&self) -> geometry::Point {
+        self.point().into()
+    }
+}
@@ -3,18 +3,138 @@
 
 #![no_std]
 
-use core::{marker::PhantomData, str};
+#[cfg(feature = "embedded-graphics")]
+mod graphics;
+#[cfg(feature = "mock")]
+mod mock;
+mod poller;
+mod tracker;
 
-const GT911_I2C_ADDR_BA: u8 = 0x5D;
+use core::{
+    future::Future,
+    marker::PhantomData,
+    pin::pin,
+    str,
+    sync::atomic::{AtomicBool, Ordering},
+    task,
+};
+use embedded_hal::i2c::{ErrorKind, Operation};
+
+#[cfg(feature = "mock")]
+pub use mock::Gt911Mock;
+pub use poller::{Poll, TouchPoller};
+pub use tracker::{
+    PrimaryPolicy, Smoothing, TouchEvent, TouchTracker, TrackedFrame, DEFAULT_HISTORY_LEN,
+};
+
+/// Default I2C address (selected when INT is low while RESET is released)
+pub const ADDR_5D: u8 = 0x5D;
+/// Alternative I2C address (selected when INT is high while RESET is released)
+pub const ADDR_14: u8 = 0x14;
 const GT911_PRODUCT_ID_REG: u16 = 0x8140;
+const GT911_GESTURE_REG: u16 = 0x814B;
 const GT911_TOUCHPOINT_STATUS_REG: u16 = 0x814E;
 const GT911_TOUCHPOINT_1_REG: u16 = 0x814F;
 const GT911_COMMAND_REG: u16 = 0x8040;
+const GT911_CONFIG_START_REG: u16 = 0x8047;
+const GT911_X_OUTPUT_MAX_REG: u16 = 0x8048;
+const GT911_TOUCH_NUMBER_REG: u16 = 0x804C;
+const GT911_MODULE_SWITCH_1_REG: u16 = 0x804D;
+const GT911_FILTER_REG: u16 = 0x8050;
+const GT911_LOW_POWER_CONTROL_REG: u16 = 0x8055;
+const GT911_REFRESH_RATE_REG: u16 = 0x8056;
+const GT911_X_THRESHOLD_REG: u16 = 0x8057;
+const GT911_BORDER_SPACE_REG: u16 = 0x805B;
+const GT911_STRETCH_R0_REG: u16 = 0x805E;
+const GT911_DRIVER_GROUP_A_REG: u16 = 0x8062;
+const GT911_FREQ_HOPPING_START_REG: u16 = 0x807A;
+const GT911_KEY_1_REG: u16 = 0x8093;
+const GT911_RAW_DATA_REG: u16 = 0x8B98;
+const GT911_CONFIG_CHECKSUM_REG: u16 = 0x80FF;
+const GT911_CONFIG_FRESH_REG: u16 = 0x8100;
+
+const COMMAND_READ_COORDINATES: u8 = 0;
+const COMMAND_READ_RAW_DATA: u8 = 1;
+const COMMAND_CALIBRATE_BASELINE: u8 = 4;
+// the touch application answers this in the command register by setting the byte after it to 0xAA
+const COMMAND_ESD_CHECK: u8 = 0xAA;
+// time for the application to run its main loop (one scan) and process a command
+const COMMAND_SETTLE_MS: u32 = 50;
+// the controller has no completion flag for a baseline calibration so wait long enough for several scans
+const CALIBRATE_SETTLE_MS: u32 = 200;
+
+const MODULE_SWITCH_1_X2Y: u8 = 0x08;
+const FILTER_NORMAL_MASK: u8 = 0x3F;
+
+const TOUCH_KEY_CONFIG_LEN: usize = 9;
+// bytes per transaction of the blocking read_config_streaming
+const CONFIG_CHUNK_LEN: usize = 16;
+// product id, firmware version, x/y resolution and sensor id (0x8140-0x814A)
+const PRODUCT_INFO_LEN: usize = 11;
 
 const MAX_NUM_TOUCHPOINTS: usize = 5;
+const TORN_FRAME_RETRIES: usize = 3;
+// status polls per frame before capture_frames gives up, at 400kHz this is well over a second
+const CAPTURE_FRAME_POLLS: usize = 10_000;
 const TOUCHPOINT_ENTRY_LEN: usize = 8;
-pub const GET_TOUCH_BUF_SIZE: usize = TOUCHPOINT_ENTRY_LEN;
-pub const GET_MULTITOUCH_BUF_SIZE: usize = TOUCHPOINT_ENTRY_LEN * MAX_NUM_TOUCHPOINTS;
+/// Temp buffer size needed by the async get_touch (status register + one touch point)
+pub const GET_TOUCH_BUF_SIZE: usize = buf_size(1);
+/// Temp buffer size needed by the async get_multi_touch (status register + all touch points)
+pub const GET_MULTITOUCH_BUF_SIZE: usize = buf_size(MAX_NUM_TOUCHPOINTS);
+/// Maximum number of zones that can be passed to with_exclusion_zones
+pub const MAX_EXCLUSION_ZONES: usize = 4;
+/// Buffer size needed by read_all_slots_raw (all five 8 byte touch point slots, no status register)
+pub const ALL_SLOTS_LEN: usize = MAX_NUM_TOUCHPOINTS * TOUCHPOINT_ENTRY_LEN;
+
+/// Length of the config block (0x8047-0x80FE), it is followed by the checksum (0x80FF) and the config fresh flag (0x8100)
+pub const CONFIG_LEN: usize = 184;
+/// Temp buffer size needed by the async config setters (register address + config + checksum + fresh flag)
+pub const CONFIG_BUF_SIZE: usize = 2 + CONFIG_LEN + 2;
+/// Temp buffer size that is large enough for every async method except read_raw_diagnostics
+/// (which depends on the sensor size), use it to share one buffer across all calls
+pub const MAX_BUF_SIZE: usize = CONFIG_BUF_SIZE;
+
+/// Temp buffer size needed to read the status register and max_points touch points in one transfer
+/// e.g. buf_size(1) == GET_TOUCH_BUF_SIZE and buf_size(5) == GET_MULTITOUCH_BUF_SIZE
+pub const fn buf_size(max_points: usize) -> usize {
+    1 + TOUCHPOINT_ENTRY_LEN * max_points
+}
+
+/// Formats a whole frame of touch points on one line for logging, e.g. "2pts: #0(10,20) #1(30,40)"
+/// Implements Display and, with the defmt feature, defmt::Format without allocating
+#[derive(Debug, Clone, Copy)]
+pub struct TouchFrame<'a>(pub &'a [Point]);
+
+impl core::fmt::Display for TouchFrame<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}pts:", self.0.len())?;
+        for point in self.0 {
+            write!(f, " #{}({},{})", point.track_id, point.x, point.y)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for TouchFrame<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{}pts:", self.0.len());
+        for point in self.0 {
+            defmt::write!(f, " #{}({},{})", point.track_id, point.x, point.y);
+        }
+    }
+}
+
+/// Identifies a finger across frames, stable for as long as the finger stays down
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TrackId(pub u8);
+
+impl From<TrackId> for u8 {
+    fn from(track_id: TrackId) -> Self {
+        track_id.0
+    }
+}
 
 /// The touchpoint
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -30,6 +150,475 @@ pub struct Point {
     pub area: u16,
 }
 
+impl Point {
+    /// The finger this point belongs to as a hashable key
+    /// Use this (not the Point itself) to key per-finger state because x, y and area change every frame
+    pub fn track_id(&self) -> TrackId {
+        TrackId(self.track_id)
+    }
+
+    /// Compares the position of two points ignoring track_id and area
+    /// Points are considered equal if x and y are each within tolerance pixels of each other
+    /// which is useful for filtering out jitter when deciding whether a touch point has moved
+    pub fn approx_eq(&self, other: &Point, tolerance: u16) -> bool {
+        self.x.abs_diff(other.x) <= tolerance && self.y.abs_diff(other.y) <= tolerance
+    }
+
+    /// Returns the position normalized to the panel size as Q16.16 fixed point (x, y)
+    /// where 0 is the top/left edge and 65536 (1.0) the bottom/right edge
+    /// width and height are the panel resolution, see read_resolution
+    pub fn normalized(&self, width: u16, height: u16) -> (u32, u32) {
+        (normalize(self.x, width), normalize(self.y, height))
+    }
+
+    /// Returns the physical position from the top/left corner in millimeters as Q16.16 fixed point (x, y)
+    pub fn to_mm(&self, panel: &PanelSize) -> (u32, u32) {
+        (
+            scale_q16(self.x, panel.width, panel.width_mm),
+            scale_q16(self.y, panel.height, panel.height_mm),
+        )
+    }
+
+    /// Returns the average position of all points (e.g. the focus point of a pinch) or None if points is empty
+    /// x, y and area are averaged and rounded to the nearest integer (halves round up), track_id is the lowest
+    /// track_id of the points
+    pub fn centroid(points: &[Point]) -> Option<Point> {
+        let track_id = points.iter().map(|point| point.track_id).min()?;
+        let average = |value: fn(&Point) -> u16| {
+            let sum: u32 = points.iter().map(|point| value(point) as u32).sum();
+            let n = points.len() as u32;
+            ((sum + n / 2) / n) as u16
+        };
+        Some(Point {
+            track_id,
+            x: average(|point| point.x),
+            y: average(|point| point.y),
+            area: average(|point| point.area),
+        })
+    }
+
+    /// Returns the distance in pixels between the first two points (rounded down) or None if there are fewer than two
+    /// Compare it frame to frame to drive a pinch zoom. Integer only, no floating point needed
+    pub fn pinch_distance(points: &[Point]) -> Option<u32> {
+        match points {
+            [a, b, ..] => {
+                let dx = a.x.abs_diff(b.x) as u64;
+                let dy = a.y.abs_diff(b.y) as u64;
+                Some(isqrt(dx * dx + dy * dy) as u32)
+            }
+            _ => None,
+        }
+    }
+
+    /// Moves the point by dx and dy returning signed coordinates
+    /// Unlike Point, the result can go negative (e.g. offset correction near the edge of the screen)
+    /// so no information is lost for edge gesture detection. Coordinates saturate at the i16 limits
+    pub fn translate(&self, dx: i16, dy: i16) -> TransformedPoint {
+        TransformedPoint {
+            track_id: self.track_id,
+            x: to_i16(self.x).saturating_add(dx),
+            y: to_i16(self.y).saturating_add(dy),
+            area: self.area,
+        }
+    }
+}
+
+/// A rectangle of the panel in which touches are ignored (e.g. where the bezel overlaps the active area)
+/// see with_exclusion_zones. Coordinates are in the same units as Point (after with_offset is applied)
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExclusionZone {
+    /// Left edge
+    pub x: u16,
+    /// Top edge
+    pub y: u16,
+    /// Width, the zone covers x..x + width
+    pub width: u16,
+    /// Height, the zone covers y..y + height
+    pub height: u16,
+}
+
+impl ExclusionZone {
+    /// Whether point is inside the zone
+    pub fn contains(&self, point: &Point) -> bool {
+        (self.x as u32..self.x as u32 + self.width as u32).contains(&(point.x as u32))
+            && (self.y as u32..self.y as u32 + self.height as u32).contains(&(point.y as u32))
+    }
+}
+
+/// Resolution and physical size of the touch panel, used to convert touch points to millimeters
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanelSize {
+    /// x resolution in pixels, see read_resolution
+    pub width: u16,
+    /// y resolution in pixels, see read_resolution
+    pub height: u16,
+    /// Physical width of the active area in millimeters
+    pub width_mm: u16,
+    /// Physical height of the active area in millimeters
+    pub height_mm: u16,
+}
+
+/// A touchpoint after a software transform with signed coordinates
+/// Coordinates can be negative or beyond the screen size and it is up to the user to clamp them if needed
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransformedPoint {
+    /// The touchpoint number (zero based)
+    pub track_id: u8,
+    /// x coordinate in screen pixels
+    pub x: i16,
+    /// y coordinate in screen pixels
+    pub y: i16,
+    /// How much area the finder takes up on the touch point
+    pub area: u16,
+}
+
+impl From<&Point> for TransformedPoint {
+    fn from(point: &Point) -> Self {
+        point.translate(0, 0)
+    }
+}
+
+/// The number of drive and sense lines of the touch sensor as configured on the controller
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SensorGeometry {
+    /// Number of drive lines in driver group A
+    pub driver_group_a: u8,
+    /// Number of drive lines in driver group B
+    pub driver_group_b: u8,
+    /// Number of sense lines in sensor group A
+    pub sensor_group_a: u8,
+    /// Number of sense lines in sensor group B
+    pub sensor_group_b: u8,
+}
+
+impl SensorGeometry {
+    /// Total number of drive lines
+    pub fn drive_lines(&self) -> u8 {
+        self.driver_group_a + self.driver_group_b
+    }
+
+    /// Total number of sense lines
+    pub fn sense_lines(&self) -> u8 {
+        self.sensor_group_a + self.sensor_group_b
+    }
+}
+
+/// Border space config (0x805B-0x805C): how far in from each edge of the panel the controller stretches
+/// coordinates to reach the edge. Each side is 0-15 in controller units, 0 reports raw coordinates at the edge
+/// Lower values make touches that start at the very edge (edge swipes) more reliable to detect
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BorderSpace {
+    /// Top border
+    pub top: u8,
+    /// Bottom border
+    pub bottom: u8,
+    /// Left border
+    pub left: u8,
+    /// Right border
+    pub right: u8,
+}
+
+/// Coordinate stretch config (Stretch_R0-Stretch_R2 and Stretch_RM, 0x805E-0x8061)
+/// The controller stretches the native sensor coordinates internally in three intervals, each with its own
+/// coefficient on top of a common base, before mapping them to X_Output_Max and Y_Output_Max (set_resolution).
+/// Stretching therefore keeps the full sensor resolution, the output is still clamped to the output max.
+/// Goodix does not document the curve any further so start from the panel vendor's values and adjust from there
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StretchConfig {
+    /// Coefficient of the first interval (Stretch_R0)
+    pub r0: u8,
+    /// Coefficient of the second interval (Stretch_R1)
+    pub r1: u8,
+    /// Coefficient of the third interval (Stretch_R2)
+    pub r2: u8,
+    /// Base of all intervals (Stretch_RM)
+    pub rm: u8,
+}
+
+/// Frequency hopping config (0x807A-0x807D): the range of drive frequencies the controller may scan at
+/// When it detects noise at the current frequency it hops to another one within start..=end. Move the range away
+/// from noise sources such as the display refresh or a switching regulator if they show up as touch noise.
+/// Frequencies are in units of 2kHz (e.g. 50 is 100kHz)
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrequencyHopping {
+    /// Lowest frequency to hop to (Freq_Hopping_Start)
+    pub start: u8,
+    /// Highest frequency to hop to (Freq_Hopping_End)
+    pub end: u8,
+    /// Number of noisy scans before hopping (Noise_Detect_Times)
+    pub noise_detect_times: u8,
+    /// Raw Hopping_Flag byte (hopping enable and detection options, see the config of your panel vendor)
+    pub flags: u8,
+}
+
+/// Touch key (capacitive button) configuration
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TouchKeyConfig {
+    /// Position of keys 1-4 along the key sensor line (0 means the key is not used)
+    pub positions: [u8; 4],
+    /// Key area (Key_Area), the width of the region around each key position that registers as the key
+    pub area: u8,
+    /// Threshold for a key to be considered touched
+    pub touch_level: u8,
+    /// Threshold for a key to be considered released
+    pub leave_level: u8,
+    /// Sensitivity coefficient of keys 1-4 (0-15 each)
+    pub sensitivity: [u8; 4],
+}
+
+/// Identification of the controller and its config as returned by probe
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeInfo {
+    /// Product id, "911\0" for a GT911
+    pub product_id: [u8; 4],
+    /// Firmware version
+    pub firmware_version: u16,
+    /// Id of the sensor (panel) as strapped on the module
+    pub sensor_id: u8,
+    /// Version of the config currently loaded
+    pub config_version: u8,
+    /// x resolution in pixels
+    pub width: u16,
+    /// y resolution in pixels
+    pub height: u16,
+}
+
+impl ProbeInfo {
+    /// The product id as a string without the trailing nul characters (e.g. "911")
+    pub fn product_id_str(&self) -> Option<&str> {
+        product_id_str(&self.product_id)
+    }
+}
+
+/// Firmware and config fingerprint of the controller as returned by read_version_info
+/// The GT911 has no version or patch bytes beyond these
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionInfo {
+    /// Product id, "911\0" for a GT911
+    pub product_id: [u8; 4],
+    /// Firmware version
+    pub firmware_version: u16,
+    /// Id of the sensor (panel) as strapped on the module
+    pub sensor_id: u8,
+    /// Version of the config currently loaded
+    pub config_version: u8,
+    /// Checksum of the config currently loaded, tells apart two configs with the same version
+    pub config_checksum: u8,
+}
+
+impl VersionInfo {
+    /// The product id as a string without the trailing nul characters (e.g. "911")
+    pub fn product_id_str(&self) -> Option<&str> {
+        product_id_str(&self.product_id)
+    }
+}
+
+/// Outcome of the end-of-line checks done by self_test, every check can be inspected on its own
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestReport {
+    /// The identification read during the test
+    pub version: VersionInfo,
+    /// The product id is "911"
+    pub product_id_ok: bool,
+    /// The firmware version is neither 0x0000 nor 0xFFFF (blank or erased flash)
+    pub firmware_ok: bool,
+    /// The checksum register (0x80FF) matches the checksum calculated over the config block
+    pub config_checksum_ok: bool,
+    /// The resolution in the product info (0x8146-0x8149) is non-zero and matches X_Output_Max and
+    /// Y_Output_Max in the config, both are little-endian so a mismatch points at corrupted data
+    pub resolution_ok: bool,
+}
+
+impl SelfTestReport {
+    /// Whether every check passed
+    pub fn passed(&self) -> bool {
+        self.product_id_ok && self.firmware_ok && self.config_checksum_ok && self.resolution_ok
+    }
+}
+
+/// A named field of the config block, see read_config_field and write_config_field
+/// Multi-byte fields are little-endian
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigField {
+    /// Config_Version (0x8047), the controller only accepts a config with a version equal or higher than its own
+    ConfigVersion,
+    /// X_Output_Max (0x8048-0x8049), x resolution
+    XOutputMax,
+    /// Y_Output_Max (0x804A-0x804B), y resolution
+    YOutputMax,
+    /// Touch_Number (0x804C), maximum number of touch points reported (1-5)
+    TouchNumber,
+    /// Module_Switch1 (0x804D), INT trigger mode, X2Y and axis inversion bits
+    ModuleSwitch1,
+    /// Module_Switch2 (0x804E)
+    ModuleSwitch2,
+    /// Shake_Count (0x804F), touch debounce
+    ShakeCount,
+    /// Filter (0x8050), First_Filter and Normal_Filter (coordinate filter)
+    Filter,
+    /// Large_Touch (0x8051), number of large area touch points
+    LargeTouch,
+    /// Noise_Reduction (0x8052)
+    NoiseReduction,
+    /// Screen_Touch_Level (0x8053), threshold for a touch to be detected
+    ScreenTouchLevel,
+    /// Screen_Leave_Level (0x8054), threshold for a touch to be released
+    ScreenLeaveLevel,
+    /// Low_Power_Control (0x8055), idle timeout before entering low power mode
+    LowPowerControl,
+    /// Refresh_Rate (0x8056), coordinate report period and INT pulse width
+    RefreshRate,
+    /// X_Threshold (0x8057), minimum x movement before a move is reported
+    XThreshold,
+    /// Y_Threshold (0x8058), minimum y movement before a move is reported
+    YThreshold,
+}
+
+impl ConfigField {
+    /// The register of the (first byte of the) field
+    pub fn register(&self) -> u16 {
+        match self {
+            ConfigField::ConfigVersion => GT911_CONFIG_START_REG,
+            ConfigField::XOutputMax => GT911_X_OUTPUT_MAX_REG,
+            ConfigField::YOutputMax => GT911_X_OUTPUT_MAX_REG + 2,
+            ConfigField::TouchNumber => GT911_TOUCH_NUMBER_REG,
+            ConfigField::ModuleSwitch1 => GT911_MODULE_SWITCH_1_REG,
+            ConfigField::ModuleSwitch2 => GT911_MODULE_SWITCH_1_REG + 1,
+            ConfigField::ShakeCount => GT911_MODULE_SWITCH_1_REG + 2,
+            ConfigField::Filter => GT911_FILTER_REG,
+            ConfigField::LargeTouch => GT911_FILTER_REG + 1,
+            ConfigField::NoiseReduction => GT911_FILTER_REG + 2,
+            ConfigField::ScreenTouchLevel => GT911_FILTER_REG + 3,
+            ConfigField::ScreenLeaveLevel => GT911_FILTER_REG + 4,
+            ConfigField::LowPowerControl => GT911_LOW_POWER_CONTROL_REG,
+            ConfigField::RefreshRate => GT911_REFRESH_RATE_REG,
+            ConfigField::XThreshold => GT911_X_THRESHOLD_REG,
+            ConfigField::YThreshold => GT911_X_THRESHOLD_REG + 1,
+        }
+    }
+
+    /// The width of the field in bytes (1 or 2)
+    pub fn width(&self) -> usize {
+        match self {
+            ConfigField::XOutputMax | ConfigField::YOutputMax => 2,
+            _ => 1,
+        }
+    }
+
+    /// The largest value the field can hold
+    pub fn max(&self) -> u16 {
+        match self.width() {
+            2 => u16::MAX,
+            _ => u8::MAX as u16,
+        }
+    }
+}
+
+/// A gesture reported by gesture enabled firmware, codes as used by the Goodix reference driver
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    /// Double tap (0xCC)
+    DoubleTap,
+    /// Swipe up (0xBA)
+    SwipeUp,
+    /// Swipe down (0xAB)
+    SwipeDown,
+    /// Swipe left (0xBB)
+    SwipeLeft,
+    /// Swipe right (0xAA)
+    SwipeRight,
+    /// A letter drawn on the panel (e.g. b'c', b'e', b'm', b'o' or b'w')
+    Letter(u8),
+    /// Any other gesture code
+    Other(u8),
+}
+
+/// Decoded touch status register (0x814E) of a ready frame, see wait_ready
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TouchStatus {
+    /// Number of touch points in the frame (0 for release), clamped to 5
+    pub num_touch_points: u8,
+    /// A large area touch (e.g. a palm) was detected
+    pub large_detect: bool,
+    /// A touch key is pressed
+    pub have_key: bool,
+}
+
+/// A single touch point along with the large touch flag of the same frame, see get_touch_result
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TouchResult {
+    /// The first touch point, None for release
+    pub point: Option<Point>,
+    /// A large area touch (e.g. a palm) was detected, the point is usually not meaningful when set
+    pub large_touch: bool,
+}
+
+/// Outcome of Gt911::wait_for_release
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReleaseOutcome {
+    /// The finger was lifted (its track_id was absent from a frame)
+    Released,
+    /// The timeout completed first, the finger is still down
+    TimedOut,
+}
+
+/// Addresses of the registers used to read touch data, see with_register_map
+/// Only needed for custom firmware that moves these registers, the config block is always at 0x8047
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterMap {
+    /// Command register (0x8040)
+    pub command: u16,
+    /// Product id, the first of the product info registers (0x8140)
+    pub product_id: u16,
+    /// Touch status register (0x814E)
+    pub status: u16,
+    /// First touch point entry (0x814F), the entries of the other slots follow it
+    /// If it does not directly follow status, touch reads take an extra transaction
+    pub touchpoint_1: u16,
+}
+
+impl Default for RegisterMap {
+    /// The standard GT911 register map
+    fn default() -> Self {
+        Self {
+            command: GT911_COMMAND_REG,
+            product_id: GT911_PRODUCT_ID_REG,
+            status: GT911_TOUCHPOINT_STATUS_REG,
+            touchpoint_1: GT911_TOUCHPOINT_1_REG,
+        }
+    }
+}
+
+/// How register reads are done on the bus, see with_read_mode
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadMode {
+    /// Register address write and data read in one write_read transaction with a repeated start (the default)
+    Combined,
+    /// A write transaction with the register address followed by a separate read transaction
+    Split,
+    /// Like Split but the first byte of the read is discarded, for clones that return a junk byte first
+    /// The discarded byte goes to a small internal buffer rather than the caller buffer
+    SplitWithDummy,
+}
+
 /// Gt911 Error
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone)]
@@ -43,22 +632,168 @@ pub enum Error<E> {
     /// This means that you have polled the device again in-between it detecting any new touch data
     /// This can safely be ignored
     NotReady,
+    /// GPIO error while using the INT or RESET pin
+    Pin,
+    /// The config checksum read back from the controller does not match the config that was written
+    ConfigChecksum,
+    /// No data became available before the timeout
+    Timeout,
+    /// A touch point entry read back as all 0xFF, what a truncated read (e.g. the MCU gave up on clock stretching)
+    /// or a released bus returns. The frame is discarded rather than decoded into garbage points
+    ShortRead,
+    /// The controller kept updating the frame while it was being read and no consistent frame was read
+    /// within the retry budget, see get_touch_consistent
+    TornFrame,
 }
 
+/// Result type returned by the driver, E is the error type of the I2C bus
+pub type Result<T, E> = core::result::Result<T, Error<E>>;
+
 /// Blocking Gt911
 pub struct Gt911Blocking<I2C> {
     i2c_addr: u8, // e.g. 0x5D
+    sort_points: bool,
+    torn_frame_check: bool,
+    auto_clear: bool,
+    clear_on_init: bool,
+    require_command_write: bool,
+    reverse_slots: bool,
+    read_mode: ReadMode,
+    registers: RegisterMap,
+    // (x, y) added to every decoded point, see with_offset
+    offset: (i16, i16),
+    // touch points inside any of these are dropped, see with_exclusion_zones
+    exclusion_zones: heapless::Vec<ExclusionZone, MAX_EXCLUSION_ZONES>,
+    // whether the status register was cleared since the last read started, see last_cleared
+    last_cleared: AtomicBool,
     i2c: PhantomData<I2C>,
 }
 
 /// Use the default I2C address for communication
 impl<I2C> Default for Gt911Blocking<I2C> {
     fn default() -> Self {
+        Self::new(ADDR_5D)
+    }
+}
+
+impl<I2C> Gt911Blocking<I2C> {
+    /// Creates a new instance with a user specified 7-bit i2c address, normally ADDR_5D or ADDR_14
+    /// Debug builds panic on an address outside the valid 7-bit range (e.g. 0x5D << 1 by mistake)
+    pub fn new(i2c_addr: u8) -> Self {
+        debug_assert!(
+            (0x08..=0x77).contains(&i2c_addr),
+            "Invalid 7-bit i2c address, use ADDR_5D or ADDR_14"
+        );
         Self {
-            i2c_addr: GT911_I2C_ADDR_BA,
+            i2c_addr,
+            sort_points: false,
+            torn_frame_check: false,
+            auto_clear: true,
+            clear_on_init: true,
+            require_command_write: true,
+            reverse_slots: false,
+            read_mode: ReadMode::Combined,
+            registers: RegisterMap::default(),
+            offset: (0, 0),
+            exclusion_zones: heapless::Vec::new(),
+            last_cleared: AtomicBool::new(false),
             i2c: PhantomData,
         }
     }
+
+    /// When enabled, points returned by get_multi_touch are sorted by track_id (ascending)
+    /// The controller does not guarantee any slot order so this gives a stable finger order frame-to-frame
+    pub fn with_sorted_points(mut self, sort_points: bool) -> Self {
+        self.sort_points = sort_points;
+        self
+    }
+
+    /// When enabled, the status register is read again after the touch points and the read is retried
+    /// if it changed in-between (the controller updated the frame mid-read). This guards against torn frames
+    /// at the cost of an extra transaction per read. Err(Error::NotReady) is returned if the frame keeps changing
+    pub fn with_torn_frame_check(mut self, torn_frame_check: bool) -> Self {
+        self.torn_frame_check = torn_frame_check;
+        self
+    }
+
+    /// When enabled (the default), get_touch and get_multi_touch clear the status register after reading
+    /// When disabled the frame stays ready so that several reads (e.g. touch points and keys) can be made against it,
+    /// call clear_status once they are done to let the controller report the next frame
+    pub fn with_auto_clear(mut self, auto_clear: bool) -> Self {
+        self.auto_clear = auto_clear;
+        self
+    }
+
+    /// When enabled (the default), init clears the status register as its last step
+    /// Disable it in interrupt driven setups so that a touch that happened during boot is not lost
+    /// and the first read after init still sees the pending frame
+    pub fn with_clear_on_init(mut self, clear_on_init: bool) -> Self {
+        self.clear_on_init = clear_on_init;
+        self
+    }
+
+    /// When enabled (the default), init fails if the write that puts the controller into coordinate mode fails
+    /// Disable it for boards where that write occasionally NAKs on a healthy controller, init then ignores
+    /// the failure and goes on to the product id check, which still catches a controller that is not there
+    pub fn with_require_command_write(mut self, require_command_write: bool) -> Self {
+        self.require_command_write = require_command_write;
+        self
+    }
+
+    /// When enabled, multi-touch reads return the touch point slots in reverse order (last reported slot first)
+    /// For panels that fill the slots in reverse so that the primary finger consistently ends up at index 0
+    /// Only the order changes, get_touch still reads the first slot. with_sorted_points takes precedence
+    pub fn with_reverse_slots(mut self, reverse_slots: bool) -> Self {
+        self.reverse_slots = reverse_slots;
+        self
+    }
+
+    /// Selects how registers are read (ReadMode::Combined by default)
+    /// Only change this for clones that don't handle a combined write_read (repeated start) correctly
+    pub fn with_read_mode(mut self, read_mode: ReadMode) -> Self {
+        self.read_mode = read_mode;
+        self
+    }
+
+    /// Overrides the registers used to read touch data (RegisterMap::default(), the standard map, by default)
+    /// For custom firmware that remaps the command, product id, status or touch point registers
+    pub fn with_register_map(mut self, registers: RegisterMap) -> Self {
+        self.registers = registers;
+        self
+    }
+
+    /// Whether the last get_touch, get_multi_touch, get_touches or get_primary_touch call (or a clear_status since) cleared
+    /// the status register. False after a read that returned an error (e.g. Err(Error::NotReady)) or when
+    /// auto clear is disabled, useful to see why a following read returns Err(Error::NotReady) or the same frame again
+    pub fn last_cleared(&self) -> bool {
+        self.last_cleared.load(Ordering::Relaxed)
+    }
+
+    /// Adds a constant offset to the x and y of every touch point read (0, 0 by default)
+    /// A lightweight fix for a panel mounted a few pixels off, the result is clamped to 0..=u16::MAX
+    pub fn with_offset(mut self, x_offset: i16, y_offset: i16) -> Self {
+        self.offset = (x_offset, y_offset);
+        self
+    }
+
+    /// The (x, y) offset added to every touch point read, see with_offset
+    pub fn offset(&self) -> (i16, i16) {
+        self.offset
+    }
+
+    /// Drops every touch point that falls inside any of zones (none by default), e.g. to mask a bezel that
+    /// overlaps the active area on several edges. Points are checked after with_offset is applied.
+    /// The remaining points keep their order, a frame where every point was dropped reads like a release frame
+    /// The zones are copied onto the driver so they can be computed at runtime (e.g. from read_resolution)
+    /// Panics if there are more than MAX_EXCLUSION_ZONES zones
+    pub fn with_exclusion_zones(mut self, zones: &[ExclusionZone]) -> Self {
+        assert!(
+            zones.len() <= MAX_EXCLUSION_ZONES,
+            "Too many exclusion zones, at most MAX_EXCLUSION_ZONES"
+        );
+        self.exclusion_zones = zones.iter().copied().collect();
+        self
+    }
 }
 
 /// Blocking Gt911 implementation
@@ -66,23 +801,18 @@ impl<I2C, E> Gt911Blocking<I2C>
 where
     I2C: embedded_hal::i2c::I2c<Error = E>,
 {
-    /// Creates a new instance with a user specified i2c address
-    pub fn new(i2c_addr: u8) -> Self {
-        Self {
-            i2c_addr,
-            i2c: PhantomData,
-        }
-    }
-
-    /// Checks the ProductId for a "911\0" string response and resets the status register
+    /// Checks the ProductId for a "911\0" string response and resets the status register (see with_clear_on_init)
     /// Only needs to be called once on startup
-    pub fn init(&self, i2c: &mut I2C) -> Result<(), Error<E>> {
+    pub fn init(&self, i2c: &mut I2C) -> Result<(), E> {
         // switch to command mode
-        self.write(i2c, GT911_COMMAND_REG, 0)?;
+        let written = self.write(i2c, self.registers.command, COMMAND_READ_COORDINATES);
+        if self.require_command_write {
+            written?;
+        }
 
         // read the product_id and confirm that it is expected
         let mut read = [0u8; 4];
-        self.read(i2c, GT911_PRODUCT_ID_REG, &mut read)?;
+        self.read(i2c, self.registers.product_id, &mut read)?;
         match str::from_utf8(&read) {
             Ok(product_id) => {
                 if product_id != "911\0" {
@@ -95,27 +825,37 @@ where
         }
 
         // clear status register
-        self.write(i2c, GT911_TOUCHPOINT_STATUS_REG, 0)?;
+        if self.clear_on_init {
+            self.write(i2c, self.registers.status, 0)?;
+        }
         Ok(())
     }
 
     /// Gets a single touch point
     /// Returns Ok(None) for release, Some(point) for press or move and Err(Error::NotReady) for no data
-    pub fn get_touch(&self, i2c: &mut I2C) -> Result<Option<Point>, Error<E>> {
-        let num_touch_points = self.get_num_touch_points(i2c)?;
-
-        let point = if num_touch_points > 0 {
-            let mut read = [0u8; TOUCHPOINT_ENTRY_LEN];
-            self.read(i2c, GT911_TOUCHPOINT_1_REG, &mut read)?;
-            let point = decode_point(&read);
-            Some(point)
-        } else {
-            None
-        };
+    pub fn get_touch(&self, i2c: &mut I2C) -> Result<Option<Point>, E> {
+        self.last_cleared.store(false, Ordering::Relaxed);
+        let points = self.read_points(i2c, 1)?;
 
-        // clear status register
-        self.write(i2c, GT911_TOUCHPOINT_STATUS_REG, 0)?;
-        Ok(point)
+        if self.auto_clear {
+            self.clear_status(i2c)?;
+        }
+        Ok(points.first().cloned())
+    }
+
+    /// Gets a single touch point along with the large touch (palm) flag of the same frame
+    /// Returns point None for release, Some(point) for press or move and Err(Error::NotReady) for no data
+    pub fn get_touch_result(&self, i2c: &mut I2C) -> Result<TouchResult, E> {
+        self.last_cleared.store(false, Ordering::Relaxed);
+        let (status, points) = self.read_status_and_points(i2c, 1)?;
+
+        if self.auto_clear {
+            self.clear_status(i2c)?;
+        }
+        Ok(TouchResult {
+            point: points.first().cloned(),
+            large_touch: decode_touch_status(status)?.large_detect,
+        })
     }
 
     /// Gets multiple stack allocated touch points (0-5 points)
@@ -123,223 +863,2455 @@ where
     pub fn get_multi_touch(
         &self,
         i2c: &mut I2C,
-    ) -> Result<heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>, Error<E>> {
-        let num_touch_points = self.get_num_touch_points(i2c)?;
+    ) -> Result<heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>, E> {
+        self.last_cleared.store(false, Ordering::Relaxed);
+        let mut points = self.read_points(i2c, MAX_NUM_TOUCHPOINTS)?;
 
-        let points = if num_touch_points > 0 {
-            assert!(num_touch_points <= MAX_NUM_TOUCHPOINTS);
-            let mut points = heapless::Vec::new();
+        if self.sort_points {
+            points.sort_unstable_by_key(|point| point.track_id);
+        }
 
-            // read touch points
-            let mut read = [0u8; TOUCHPOINT_ENTRY_LEN * MAX_NUM_TOUCHPOINTS];
-            self.read(
-                i2c,
-                GT911_TOUCHPOINT_1_REG,
-                &mut read[..TOUCHPOINT_ENTRY_LEN * num_touch_points],
-            )?;
+        if self.auto_clear {
+            self.clear_status(i2c)?;
+        }
+        Ok(points)
+    }
 
-            for n in 0..num_touch_points {
-                let start = n * TOUCHPOINT_ENTRY_LEN;
-                let point = decode_point(&read[start..start + TOUCHPOINT_ENTRY_LEN]);
-                points.push(point).ok();
+    /// Polls several devices sharing the same bus in one pass (e.g. two panels at 0x5D and 0x14)
+    /// f is called with the index (into touches) of every device that has new data along with the result of
+    /// its get_multi_touch call. Devices that return Err(Error::NotReady) are skipped
+    pub fn poll_many(
+        touches: &[Self],
+        i2c: &mut I2C,
+        mut f: impl FnMut(usize, Result<heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>, E>),
+    ) {
+        for (index, touch) in touches.iter().enumerate() {
+            match touch.get_multi_touch(i2c) {
+                Err(Error::NotReady) => {}
+                result => f(index, result),
             }
+        }
+    }
 
-            points
-        } else {
-            heapless::Vec::new()
-        };
+    /// Sets the number of seconds without a touch before the controller drops into its low power (green) mode
+    /// seconds must be 0-15. Lower values save power at the cost of a slower first touch after idling
+    /// The controller does not report when it enters or leaves this mode, see Power management in the README
+    pub fn set_idle_timeout(&self, i2c: &mut I2C, seconds: u8) -> Result<(), E> {
+        assert!(seconds <= 0x0F);
+        self.update_config(i2c, |config| {
+            let reg = &mut config[config_offset(GT911_LOW_POWER_CONTROL_REG)];
+            *reg = (*reg & 0xF0) | seconds;
+        })
+    }
 
-        // clear status register
-        self.write(i2c, GT911_TOUCHPOINT_STATUS_REG, 0)?;
-        Ok(points)
+    /// Reads the number of seconds without a touch before the controller enters low power (green) mode
+    pub fn read_idle_timeout(&self, i2c: &mut I2C) -> Result<u8, E> {
+        let mut read = [0u8; 1];
+        self.read(i2c, GT911_LOW_POWER_CONTROL_REG, &mut read)?;
+        Ok(read[0] & 0x0F)
+    }
+
+    /// Sets the coordinate report (scan) period to 5 + period milliseconds (low nibble of Refresh_Rate, 0x8056)
+    /// period must be 0-15. Longer periods reduce power consumption and interrupt load
+    /// This is the normal mode rate, the GT911 has no separate green (low power) mode rate register
+    /// (0x8057 is X_Threshold), green mode is only entered after set_idle_timeout seconds without a touch
+    pub fn set_refresh_rate(&self, i2c: &mut I2C, period: u8) -> Result<(), E> {
+        assert!(period <= 0x0F);
+        self.update_config(i2c, |config| {
+            let reg = &mut config[config_offset(GT911_REFRESH_RATE_REG)];
+            *reg = (*reg & 0xF0) | period;
+        })
+    }
+
+    /// Reads the normal mode coordinate report (scan) period, the actual period is 5 + period milliseconds
+    pub fn read_refresh_rate(&self, i2c: &mut I2C) -> Result<u8, E> {
+        let mut read = [0u8; 1];
+        self.read(i2c, GT911_REFRESH_RATE_REG, &mut read)?;
+        Ok(read[0] & 0x0F)
+    }
+
+    /// Enables or disables swapping of the x and y axes by the controller itself (X2Y bit of Module_Switch1)
+    /// Useful for landscape mounted panels because no per-point transform is needed in software
+    /// Note that the configured x and y output maximums are not swapped along with the axes
+    pub fn set_swap_xy(&self, i2c: &mut I2C, enabled: bool) -> Result<(), E> {
+        self.update_config(i2c, |config| {
+            let reg = &mut config[config_offset(GT911_MODULE_SWITCH_1_REG)];
+            *reg = (*reg & !MODULE_SWITCH_1_X2Y) | if enabled { MODULE_SWITCH_1_X2Y } else { 0 };
+        })
     }
 
-    fn get_num_touch_points(&self, i2c: &mut I2C) -> Result<usize, Error<E>> {
-        // read coords
+    /// Reads whether the controller is configured to swap the x and y axes
+    pub fn read_swap_xy(&self, i2c: &mut I2C) -> Result<bool, E> {
         let mut read = [0u8; 1];
-        self.read(i2c, GT911_TOUCHPOINT_STATUS_REG, &mut read)?;
+        self.read(i2c, GT911_MODULE_SWITCH_1_REG, &mut read)?;
+        Ok(read[0] & MODULE_SWITCH_1_X2Y != 0)
+    }
 
-        let status = read[0];
-        let ready = (status & 0x80) > 0;
-        let num_touch_points = (status & 0x0F) as usize;
+    /// Reads the config block (0x8047-0x80FE) into config which should be at least CONFIG_LEN bytes in length
+    /// The checksum and config fresh flag that follow the block are not included
+    pub fn read_config(&self, i2c: &mut I2C, config: &mut [u8]) -> Result<(), E> {
+        assert!(
+            config.len() >= CONFIG_LEN,
+            "Buffer too small, use CONFIG_LEN"
+        );
+        self.read(i2c, GT911_CONFIG_START_REG, &mut config[..CONFIG_LEN])
+    }
 
-        if ready {
-            Ok(num_touch_points)
-        } else {
-            Err(Error::NotReady)
+    /// Writes the config block (0x8047-0x80FE) along with its checksum and sets the config fresh flag
+    /// so that the controller applies it. config should be at least CONFIG_LEN bytes in length
+    /// NOTE: the controller stores the config in flash so avoid writing it repeatedly, see ensure_config
+    pub fn write_config(&self, i2c: &mut I2C, config: &[u8]) -> Result<(), E> {
+        assert!(
+            config.len() >= CONFIG_LEN,
+            "Buffer too small, use CONFIG_LEN"
+        );
+        let mut buf = [0u8; CONFIG_BUF_SIZE];
+        buf[2..2 + CONFIG_LEN].copy_from_slice(&config[..CONFIG_LEN]);
+        prepare_config_write(&mut buf);
+        i2c.write(self.i2c_addr, &buf).map_err(Error::I2C)
+    }
+
+    /// Writes the desired config only if it differs from the config currently on the controller
+    /// Returns true if a write occurred. Use this on every startup to avoid needless flash wear
+    pub fn ensure_config(&self, i2c: &mut I2C, desired: &[u8]) -> Result<bool, E> {
+        assert!(
+            desired.len() >= CONFIG_LEN,
+            "Buffer too small, use CONFIG_LEN"
+        );
+        let mut current = [0u8; CONFIG_LEN];
+        self.read_config(i2c, &mut current)?;
+        if current[..] == desired[..CONFIG_LEN] {
+            return Ok(false);
         }
+
+        self.write_config(i2c, desired)?;
+        Ok(true)
     }
 
-    fn write(&self, i2c: &mut I2C, register: u16, value: u8) -> Result<(), Error<E>> {
-        let register = register.to_be_bytes();
-        let cmd = [register[0], register[1], value];
-        i2c.write(self.i2c_addr, &cmd).map_err(Error::I2C)
+    /// Releases an INT line that is stuck asserted
+    /// The GT911 keeps INT asserted (and stops reporting new touch data) until the status register is cleared.
+    /// If a read is skipped or aborted before the clear (e.g. a bug elsewhere or an early return) an edge triggered
+    /// interrupt handler will never fire again. This switches back to coordinate reading mode and clears
+    /// the status register without reading anything. Call init again if the controller still does not respond
+    pub fn recover(&self, i2c: &mut I2C) -> Result<(), E> {
+        self.resume_reading(i2c)
     }
 
-    fn read(&self, i2c: &mut I2C, register: u16, buf: &mut [u8]) -> Result<(), Error<E>> {
-        i2c.write_read(self.i2c_addr, &register.to_be_bytes(), buf)
-            .map_err(Error::I2C)
+    /// Reads the number of drive and sense lines configured for the sensor (config 0x8062-0x8064)
+    /// Useful to sanity check that you are talking to the expected module or to validate a config before writing it
+    pub fn read_sensor_geometry(&self, i2c: &mut I2C) -> Result<SensorGeometry, E> {
+        let mut read = [0u8; 3];
+        self.read(i2c, GT911_DRIVER_GROUP_A_REG, &mut read)?;
+        Ok(decode_sensor_geometry(&read))
     }
-}
 
-/// Async Gt911
-pub struct Gt911<I2C> {
-    i2c_addr: u8, // e.g. 0x5D
-    i2c: PhantomData<I2C>,
-}
+    /// Sets the x and y output resolution (X_Output_Max and Y_Output_Max in the config)
+    /// Reported coordinates are scaled by the controller to fit this resolution
+    pub fn set_resolution(&self, i2c: &mut I2C, width: u16, height: u16) -> Result<(), E> {
+        self.update_config(i2c, |config| {
+            let offset = config_offset(GT911_X_OUTPUT_MAX_REG);
+            config[offset..offset + 2].copy_from_slice(&width.to_le_bytes());
+            config[offset + 2..offset + 4].copy_from_slice(&height.to_le_bytes());
+        })
+    }
 
-/// Use the default I2C address for communication
-impl<I2C> Default for Gt911<I2C> {
-    fn default() -> Self {
-        Self {
-            i2c_addr: GT911_I2C_ADDR_BA,
-            i2c: PhantomData,
-        }
+    /// Reads the configured x and y output resolution as (width, height)
+    pub fn read_resolution(&self, i2c: &mut I2C) -> Result<(u16, u16), E> {
+        let mut read = [0u8; 4];
+        self.read(i2c, GT911_X_OUTPUT_MAX_REG, &mut read)?;
+        Ok(decode_resolution(&read))
     }
-}
 
-/// Async Gt911 implementation
-impl<I2C, E> Gt911<I2C>
-where
-    I2C: embedded_hal_async::i2c::I2c<Error = E>,
-{
-    /// Creates a new instance with a user specified i2c address
-    pub fn new(i2c_addr: u8) -> Self {
-        Self {
-            i2c_addr,
-            i2c: PhantomData,
+    /// Checks whether anything acknowledges the i2c address using a single byte read
+    /// Returns Ok(false) if the address is not acknowledged. This is much cheaper than init because
+    /// it does not switch modes or validate the product id so it is suitable for hot-plug detection
+    pub fn is_present(&self, i2c: &mut I2C) -> Result<bool, E>
+    where
+        E: embedded_hal::i2c::Error,
+    {
+        let mut read = [0u8; 1];
+        match i2c.read(self.i2c_addr, &mut read) {
+            Ok(()) => Ok(true),
+            Err(e) if matches!(e.kind(), ErrorKind::NoAcknowledge(_)) => Ok(false),
+            Err(e) => Err(Error::I2C(e)),
         }
     }
 
-    /// Checks the ProductId for a "911\0" string response and resets the status register
-    /// Only needs to be called once on startup
-    /// buf is a temp read buffer and should be at least 4 bytes in length
-    pub async fn init(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<(), Error<E>> {
+    /// Reads the touch key configuration (key positions, thresholds and sensitivities at 0x8093-0x809B)
+    pub fn read_touch_keys(&self, i2c: &mut I2C) -> Result<TouchKeyConfig, E> {
+        let mut read = [0u8; TOUCH_KEY_CONFIG_LEN];
+        self.read(i2c, GT911_KEY_1_REG, &mut read)?;
+        Ok(decode_touch_keys(&read))
+    }
+
+    /// Writes the touch key configuration with checksum refresh
+    /// Use this to tune the key sensitivity independently from the main panel touch thresholds
+    pub fn set_touch_keys(&self, i2c: &mut I2C, keys: &TouchKeyConfig) -> Result<(), E> {
+        self.update_config(i2c, |config| {
+            let offset = config_offset(GT911_KEY_1_REG);
+            encode_touch_keys(keys, &mut config[offset..offset + TOUCH_KEY_CONFIG_LEN]);
+        })
+    }
+
+    /// Resets the controller and selects a new i2c address (0x5D or 0x14) returning a driver bound to it
+    /// The GT911 has no address register, the address is latched from the INT pin level when RESET is released.
+    /// This drives both pins through the datasheet power-on sequence: RESET low, INT set to select the address,
+    /// RESET high, then INT held low for 50ms. Afterwards the caller MUST switch int back to a floating input
+    /// so that the controller can drive it, and then call init on the returned driver
+    pub fn change_address(
+        self,
+        rst: &mut impl embedded_hal::digital::OutputPin,
+        int: &mut impl embedded_hal::digital::OutputPin,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+        i2c_addr: u8,
+    ) -> Result<Self, E> {
+        assert!(i2c_addr == ADDR_5D || i2c_addr == ADDR_14);
+        rst.set_low().map_err(|_| Error::Pin)?;
+        delay.delay_ms(20);
+        int.set_state((i2c_addr == ADDR_14).into())
+            .map_err(|_| Error::Pin)?;
+        delay.delay_ms(2);
+        rst.set_high().map_err(|_| Error::Pin)?;
+        delay.delay_ms(6);
+        int.set_low().map_err(|_| Error::Pin)?;
+        delay.delay_ms(50);
+        Ok(Self { i2c_addr, ..self })
+    }
+
+    /// Writes the config block like write_config and then reads back the checksum register
+    /// Returns Err(Error::ConfigChecksum) if it does not match the checksum calculated for config
+    pub fn write_config_verified(&self, i2c: &mut I2C, config: &[u8]) -> Result<(), E> {
+        self.write_config(i2c, config)?;
+        let mut read = [0u8; 1];
+        self.read(i2c, GT911_CONFIG_CHECKSUM_REG, &mut read)?;
+        if read[0] != config_checksum(&config[..CONFIG_LEN]) {
+            return Err(Error::ConfigChecksum);
+        }
+        Ok(())
+    }
+
+    /// Reads the config checksum (0x80FF) and config fresh flag (0x8100) in one read as (checksum, fresh_flag)
+    /// The controller clears the fresh flag once it has applied a new config so this confirms a config write
+    pub fn read_config_status(&self, i2c: &mut I2C) -> Result<(u8, u8), E> {
+        let mut read = [0u8; 2];
+        self.read(i2c, GT911_CONFIG_CHECKSUM_REG, &mut read)?;
+        Ok((read[0], read[1]))
+    }
+
+    /// Switches the controller back to reading coordinates (writes 0 to the command register) and clears
+    /// the status register. Use this after config or diagnostic operations that leave the controller in a
+    /// different command mode, without having to go through a full init
+    pub fn resume_reading(&self, i2c: &mut I2C) -> Result<(), E> {
+        self.write(i2c, self.registers.command, COMMAND_READ_COORDINATES)?;
+        self.write(i2c, self.registers.status, 0)
+    }
+
+    /// Reads everything needed to confirm the part and its config in one call (board bring-up diagnostics)
+    /// Unlike init this does not validate the product id or change the controller mode
+    pub fn probe(&self, i2c: &mut I2C) -> Result<ProbeInfo, E> {
+        let mut read = [0u8; PRODUCT_INFO_LEN];
+        self.read(i2c, self.registers.product_id, &mut read)?;
+        let mut config_version = [0u8; 1];
+        self.read(i2c, GT911_CONFIG_START_REG, &mut config_version)?;
+        Ok(decode_probe_info(&read, config_version[0]))
+    }
+
+    /// Reads the raw 8 byte entry of touch point slot (0-4) without decoding it or touching the status register
+    /// Layout: track_id, x (le u16), y (le u16), area (le u16), reserved
+    pub fn read_touchpoint_raw(
+        &self,
+        i2c: &mut I2C,
+        slot: u8,
+        buf: &mut [u8; TOUCHPOINT_ENTRY_LEN],
+    ) -> Result<(), E> {
+        self.read(i2c, touchpoint_reg(self.registers.touchpoint_1, slot), buf)
+    }
+
+    /// Sets the strength of the controller's built-in coordinate filter (Normal_Filter, bits 5-0 of config 0x8050)
+    /// strength must be 0-63, the filter window is strength * 4. Higher values smooth out jitter at the cost
+    /// of responsiveness to small movements. The First_Filter bits are left unchanged
+    pub fn set_coordinate_filter(&self, i2c: &mut I2C, strength: u8) -> Result<(), E> {
+        assert!(strength <= FILTER_NORMAL_MASK);
+        self.update_config(i2c, |config| {
+            let reg = &mut config[config_offset(GT911_FILTER_REG)];
+            *reg = (*reg & !FILTER_NORMAL_MASK) | strength;
+        })
+    }
+
+    /// Reads the strength of the controller's built-in coordinate filter (0-63)
+    pub fn read_coordinate_filter(&self, i2c: &mut I2C) -> Result<u8, E> {
+        let mut read = [0u8; 1];
+        self.read(i2c, GT911_FILTER_REG, &mut read)?;
+        Ok(read[0] & FILTER_NORMAL_MASK)
+    }
+
+    /// Sets the maximum number of touch points the controller scans for and reports (Touch_Number, 1-5)
+    /// Setting this to 1 stops the controller from tracking extra fingers (e.g. a resting palm) which
+    /// saves power and bus traffic compared to discarding the extra points in software
+    pub fn set_max_touches(&self, i2c: &mut I2C, n: u8) -> Result<(), E> {
+        assert!((1..=MAX_NUM_TOUCHPOINTS as u8).contains(&n));
+        self.update_config(i2c, |config| {
+            let reg = &mut config[config_offset(GT911_TOUCH_NUMBER_REG)];
+            *reg = (*reg & 0xF0) | n;
+        })
+    }
+
+    /// Reads the maximum number of touch points the controller scans for (Touch_Number)
+    pub fn read_max_touches(&self, i2c: &mut I2C) -> Result<u8, E> {
+        let mut read = [0u8; 1];
+        self.read(i2c, GT911_TOUCH_NUMBER_REG, &mut read)?;
+        Ok(read[0] & 0x0F)
+    }
+
+    /// Sets the width of the INT pulse sent for each touch report (upper nibble of Refresh_Rate, 0x8056)
+    /// width must be 0-15. Widen it if the MCU misses short edges when edge triggered
+    /// Note that 0x80D5 (mentioned in some vendor notes) is a driver channel map entry, not an interrupt timing setting
+    pub fn set_interrupt_pulse_width(&self, i2c: &mut I2C, width: u8) -> Result<(), E> {
+        assert!(width <= 0x0F);
+        self.update_config(i2c, |config| {
+            let reg = &mut config[config_offset(GT911_REFRESH_RATE_REG)];
+            *reg = (*reg & 0x0F) | (width << 4);
+        })
+    }
+
+    /// Reads the configured width of the INT pulse sent for each touch report (0-15)
+    pub fn read_interrupt_pulse_width(&self, i2c: &mut I2C) -> Result<u8, E> {
+        let mut read = [0u8; 1];
+        self.read(i2c, GT911_REFRESH_RATE_REG, &mut read)?;
+        Ok(read[0] >> 4)
+    }
+
+    /// Returns a TouchPoller that reads touch points one I2C transaction per poll call
+    /// Use this instead of get_multi_touch in cooperative loops that can't block for a whole read
+    pub fn poller(&self) -> TouchPoller<'_, I2C> {
+        TouchPoller::new(self)
+    }
+
+    /// Reads the gesture register (0x814B) and clears it so that each gesture is returned exactly once
+    /// Returns Ok(None) if no gesture is latched. The gesture code stays latched until cleared so
+    /// reading it without clearing reports the same gesture over and over
+    /// Only gesture enabled firmware reports gestures, the touch status register is not touched
+    pub fn take_gesture(&self, i2c: &mut I2C) -> Result<Option<Gesture>, E> {
+        let mut read = [0u8; 1];
+        self.read(i2c, GT911_GESTURE_REG, &mut read)?;
+        let gesture = decode_gesture(read[0]);
+        if gesture.is_some() {
+            self.write(i2c, GT911_GESTURE_REG, 0)?;
+        }
+        Ok(gesture)
+    }
+
+    /// Expert / bench feature: reads the raw capacitance data of every sensor node into buf
+    /// Useful to find a dead or flaky sensor channel (e.g. a cracked trace reads far off its neighbours)
+    /// Each node is a big-endian u16, size buf as drive_lines * sense_lines * 2 (see read_sensor_geometry)
+    /// Switches the controller to raw data mode and returns Err(Error::NotReady) until a raw frame is available,
+    /// call it again until it succeeds. Once read the controller is put back into coordinate mode
+    /// Touch reporting is suspended while in raw data mode
+    pub fn read_raw_diagnostics(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<(), E> {
+        self.write(i2c, self.registers.command, COMMAND_READ_RAW_DATA)?;
+        decode_num_touch_points(self.read_status(i2c)?)?;
+        self.read(i2c, GT911_RAW_DATA_REG, buf)?;
+        self.write(i2c, self.registers.status, 0)?;
+        self.write(i2c, self.registers.command, COMMAND_READ_COORDINATES)
+    }
+
+    /// Clears the status register so the controller can report the next frame
+    /// Only needed when auto clear is disabled (see with_auto_clear)
+    pub fn clear_status(&self, i2c: &mut I2C) -> Result<(), E> {
+        self.write(i2c, self.registers.status, 0)?;
+        self.last_cleared.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Gets the first touch point with the least bus time: the status register and the first touch point
+    /// are read in one 9 byte transaction starting at 0x814E, followed by the status clear (if auto clear is on)
+    /// Returns Ok(None) for release, Some(point) for press or move and Err(Error::NotReady) for no data
+    /// The torn frame check is not done since status and point come from the same transaction
+    pub fn get_primary_touch(&self, i2c: &mut I2C) -> Result<Option<Point>, E> {
+        self.last_cleared.store(false, Ordering::Relaxed);
+        let mut read = [0u8; GET_TOUCH_BUF_SIZE];
+        self.read_frame(i2c, &mut read)?;
+        let point = match decode_num_touch_points(read[0])? {
+            0 => None,
+            _ => Some(decode_entry(&read[1..], self.offset)?),
+        }
+        .filter(|point| !excluded(&self.exclusion_zones, point));
+
+        if self.auto_clear {
+            self.clear_status(i2c)?;
+        }
+        Ok(point)
+    }
+
+    /// Reads the config block the controller is actually running with after a write_config
+    /// Returns Err(Error::NotReady) while the config fresh flag is still set (the new config has not been applied yet),
+    /// call it again until it succeeds. Diff the result against what was written to catch fields the controller
+    /// clamped or rejected. config should be at least CONFIG_LEN bytes in length
+    pub fn read_effective_config(&self, i2c: &mut I2C, config: &mut [u8]) -> Result<(), E> {
+        let mut read = [0u8; 1];
+        self.read(i2c, GT911_CONFIG_FRESH_REG, &mut read)?;
+        if read[0] != 0 {
+            return Err(Error::NotReady);
+        }
+        self.read_config(i2c, config)
+    }
+
+    /// Reads the config block (0x8047-0x80FE) in small chunks, each its own transaction, and calls f with each
+    /// chunk in order. Trades bus transactions for RAM when a CONFIG_LEN buffer can't be spared
+    pub fn read_config_streaming(&self, i2c: &mut I2C, mut f: impl FnMut(&[u8])) -> Result<(), E> {
+        let mut read = [0u8; CONFIG_CHUNK_LEN];
+        for offset in (0..CONFIG_LEN).step_by(CONFIG_CHUNK_LEN) {
+            let read = &mut read[..CONFIG_CHUNK_LEN.min(CONFIG_LEN - offset)];
+            self.read(i2c, GT911_CONFIG_START_REG + offset as u16, read)?;
+            f(read);
+        }
+        Ok(())
+    }
+
+    /// Low level multi-touch read that calls f(track_id, x, y, area) for every touch point instead of returning them
+    /// so the caller can build whatever structure it wants. Returns the number of touch points (0 for release)
+    /// and Err(Error::NotReady) for no data. Sorting, torn frame check and auto clear are honored
+    pub fn read_touches_with(
+        &self,
+        i2c: &mut I2C,
+        mut f: impl FnMut(u8, u16, u16, u16),
+    ) -> Result<usize, E> {
+        self.last_cleared.store(false, Ordering::Relaxed);
+        let mut read = [0u8; TOUCHPOINT_ENTRY_LEN * MAX_NUM_TOUCHPOINTS];
+        let max_retries = self.torn_frame_check.then_some(TORN_FRAME_RETRIES);
+        let (_, len) = self
+            .read_status_and_entries(i2c, &mut read, MAX_NUM_TOUCHPOINTS, max_retries)
+            .map_err(torn_frame_as_not_ready)?;
+        let count = for_each_point(
+            &read[..len],
+            self.reverse_slots,
+            self.sort_points,
+            self.offset,
+            &self.exclusion_zones,
+            |point| f(point.track_id, point.x, point.y, point.area),
+        )?;
+
+        if self.auto_clear {
+            self.clear_status(i2c)?;
+        }
+        Ok(count)
+    }
+
+    /// Gets up to N stack allocated touch points, for callers that only need a few fingers (e.g. N = 2 for pinch)
+    /// If the controller reports more touch points than N only the first N slots are read and returned,
+    /// the extra touches are silently dropped and the status register is still cleared (if auto clear is on)
+    /// Returns points.len()==0 for release, points.len()>0 for press or move and Err(Error::NotReady) for no data
+    pub fn get_touches<const N: usize>(&self, i2c: &mut I2C) -> Result<heapless::Vec<Point, N>, E> {
+        self.last_cleared.store(false, Ordering::Relaxed);
+        let points = self.read_points(i2c, N.min(MAX_NUM_TOUCHPOINTS))?;
+        let mut points: heapless::Vec<Point, N> = points.into_iter().collect();
+
+        if self.sort_points {
+            points.sort_unstable_by_key(|point| point.track_id);
+        }
+
+        if self.auto_clear {
+            self.clear_status(i2c)?;
+        }
+        Ok(points)
+    }
+
+    /// Discards any buffered touch frame so that the next read only reflects fresh input
+    /// Use it after waking from sleep or re-init to avoid acting on a stale frame (a phantom touch)
+    /// There is no need to read the coordinates first, clearing the status register drops the frame
+    pub fn flush(&self, i2c: &mut I2C) -> Result<(), E> {
+        self.clear_status(i2c)
+    }
+
+    /// Reads a single named field of the config block, see ConfigField for the available fields
+    pub fn read_config_field(&self, i2c: &mut I2C, field: ConfigField) -> Result<u16, E> {
+        let mut read = [0u8; 2];
+        let read = &mut read[..field.width()];
+        self.read(i2c, field.register(), read)?;
+        Ok(decode_config_field(read))
+    }
+
+    /// Writes a single named field of the config block, refreshing the checksum and setting the config fresh flag
+    /// value must fit the field (see ConfigField::max)
+    pub fn write_config_field(
+        &self,
+        i2c: &mut I2C,
+        field: ConfigField,
+        value: u16,
+    ) -> Result<(), E> {
+        assert!(value <= field.max());
+        self.update_config(i2c, |config| encode_config_field(field, value, config))
+    }
+
+    /// Recovery for a config write that broke touch: writes a known good config (e.g. a read_config dump taken
+    /// before experimenting) with its version byte set to 0 so the controller accepts it whatever config version
+    /// it is currently running (the version is then reset to 'A'). The GT911 keeps no factory copy of the config
+    /// that could be restored, so keep a backup. config should be at least CONFIG_LEN bytes in length
+    pub fn restore_config(&self, i2c: &mut I2C, config: &[u8]) -> Result<(), E> {
+        assert!(
+            config.len() >= CONFIG_LEN,
+            "Buffer too small, use CONFIG_LEN"
+        );
+        let mut buf = [0u8; CONFIG_BUF_SIZE];
+        buf[2..2 + CONFIG_LEN].copy_from_slice(&config[..CONFIG_LEN]);
+        buf[2] = 0;
+        prepare_config_write(&mut buf);
+        i2c.write(self.i2c_addr, &buf).map_err(Error::I2C)
+    }
+
+    /// Bench feature: captures n consecutive frames into buf for offline jitter / noise analysis
+    /// Each frame is stored compactly as its status byte followed by its touch point entries (8 bytes each,
+    /// count in the low 4 bits of the status byte, at most 5). Busy polls until each frame is ready and always clears
+    /// the status register so keep a finger on the panel while capturing. Stops early once buf can't hold another
+    /// full frame (GET_MULTITOUCH_BUF_SIZE bytes). Returns the number of bytes of buf used
+    /// Returns Err(Error::Timeout) if a frame does not become ready within 10000 polls (e.g. no finger on the panel)
+    pub fn capture_frames(&self, i2c: &mut I2C, n: usize, buf: &mut [u8]) -> Result<usize, E> {
+        let mut len = 0;
+        for _ in 0..n {
+            let frame = match buf.get_mut(len..len + GET_MULTITOUCH_BUF_SIZE) {
+                Some(frame) => frame,
+                None => break,
+            };
+            let mut polls = 0;
+            let num_touch_points = loop {
+                self.read_frame(i2c, frame)?;
+                match decode_num_touch_points(frame[0]) {
+                    Ok(num_touch_points) => break num_touch_points,
+                    Err(Error::NotReady) if polls < CAPTURE_FRAME_POLLS => polls += 1,
+                    Err(Error::NotReady) => return Err(Error::Timeout),
+                    Err(e) => return Err(e),
+                }
+            };
+            self.clear_status(i2c)?;
+            frame[0] = (frame[0] & 0xF0) | num_touch_points as u8;
+            len += buf_size(num_touch_points);
+        }
+        Ok(len)
+    }
+
+    /// Sets the number of drive and sense lines of the sensor (config 0x8062-0x8064), for authoring a config
+    /// for a custom panel. Driver groups must be 0-31 and sensor groups 0-15 lines. The channel maps that assign
+    /// the lines to controller pins are separate config fields and must match the panel too
+    pub fn set_sensor_geometry(&self, i2c: &mut I2C, geometry: &SensorGeometry) -> Result<(), E> {
+        self.update_config(i2c, |config| {
+            let offset = config_offset(GT911_DRIVER_GROUP_A_REG);
+            encode_sensor_geometry(geometry, &mut config[offset..offset + 3]);
+        })
+    }
+
+    /// Polls the status register every millisecond until a frame is ready or timeout_ms milliseconds have passed
+    /// Returns the decoded status without clearing it so the coordinates can be read next (e.g. with get_multi_touch)
+    /// or skipped based on the count. Returns Err(Error::Timeout) if no frame became ready in time
+    pub fn wait_ready(
+        &self,
+        i2c: &mut I2C,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+        timeout_ms: u32,
+    ) -> Result<TouchStatus, E> {
+        let mut elapsed_ms = 0;
+        loop {
+            match decode_touch_status(self.read_status(i2c)?) {
+                Err(Error::NotReady) if elapsed_ms < timeout_ms => {
+                    delay.delay_ms(1);
+                    elapsed_ms += 1;
+                }
+                Err(Error::NotReady) => return Err(Error::Timeout),
+                result => return result,
+            }
+        }
+    }
+
+    /// Reads the border space config, see BorderSpace
+    pub fn read_border_space(&self, i2c: &mut I2C) -> Result<BorderSpace, E> {
+        let mut read = [0u8; 2];
+        self.read(i2c, GT911_BORDER_SPACE_REG, &mut read)?;
+        Ok(decode_border_space(&read))
+    }
+
+    /// Sets the border space config, see BorderSpace. Each side must be 0-15
+    pub fn set_border_space(&self, i2c: &mut I2C, border: &BorderSpace) -> Result<(), E> {
+        assert!(border.top <= 0x0F && border.bottom <= 0x0F);
+        assert!(border.left <= 0x0F && border.right <= 0x0F);
+        self.update_config(i2c, |config| {
+            let offset = config_offset(GT911_BORDER_SPACE_REG);
+            encode_border_space(border, &mut config[offset..offset + 2]);
+        })
+    }
+
+    /// Reads the complete firmware and config fingerprint of the controller for logging and support
+    pub fn read_version_info(&self, i2c: &mut I2C) -> Result<VersionInfo, E> {
+        let mut read = [0u8; PRODUCT_INFO_LEN];
+        self.read(i2c, self.registers.product_id, &mut read)?;
+        let mut config_version = [0u8; 1];
+        self.read(i2c, GT911_CONFIG_START_REG, &mut config_version)?;
+        let mut config_checksum = [0u8; 1];
+        self.read(i2c, GT911_CONFIG_CHECKSUM_REG, &mut config_checksum)?;
+        Ok(decode_version_info(
+            &read,
+            config_version[0],
+            config_checksum[0],
+        ))
+    }
+
+    /// Sets the minimum movement (X_Threshold and Y_Threshold, config 0x8057-0x8058) before the controller
+    /// reports a new position for a finger that is down. Smaller values give smoother drawing,
+    /// larger values fewer frames for a finger that is only jittering
+    pub fn set_move_threshold(&self, i2c: &mut I2C, x: u8, y: u8) -> Result<(), E> {
+        self.update_config(i2c, |config| {
+            let offset = config_offset(GT911_X_THRESHOLD_REG);
+            config[offset] = x;
+            config[offset + 1] = y;
+        })
+    }
+
+    /// Reads the minimum movement before a new position is reported as (x, y)
+    pub fn read_move_threshold(&self, i2c: &mut I2C) -> Result<(u8, u8), E> {
+        let mut read = [0u8; 2];
+        self.read(i2c, GT911_X_THRESHOLD_REG, &mut read)?;
+        Ok((read[0], read[1]))
+    }
+
+    /// Re-calibrates the capacitance baseline the controller compares touches against (command 4)
+    /// Use this when the panel has drifted (e.g. with temperature or humidity) and touches become flaky,
+    /// instead of power cycling the controller. The panel must NOT be touched while this runs or the finger
+    /// becomes part of the baseline. There is no completion flag so this waits 200ms for the controller to
+    /// settle and then puts it back into coordinate mode. Discard the first frame or two read afterwards
+    pub fn recalibrate(
+        &self,
+        i2c: &mut I2C,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+    ) -> Result<(), E> {
+        self.write(i2c, self.registers.command, COMMAND_CALIBRATE_BASELINE)?;
+        delay.delay_ms(CALIBRATE_SETTLE_MS);
+        self.write(i2c, self.registers.command, COMMAND_READ_COORDINATES)
+    }
+
+    /// Checks whether the controller is running the touch application rather than sitting in its bootloader
+    /// Useful to decide between a reset and a reflash when init fails with Err(Error::UnexpectedProductId)
+    /// Writes the ESD check command (0xAA) to 0x8040 and waits 50ms, the application clears it and sets 0x8041
+    /// to 0xAA while the bootloader ignores it. Err(Error::I2C) means nothing acknowledged at the address at all
+    pub fn is_in_application(
+        &self,
+        i2c: &mut I2C,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+    ) -> Result<bool, E> {
+        self.write(i2c, self.registers.command + 1, 0)?;
+        self.write(i2c, self.registers.command, COMMAND_ESD_CHECK)?;
+        delay.delay_ms(COMMAND_SETTLE_MS);
+        let mut read = [0u8; 2];
+        self.read(i2c, self.registers.command, &mut read)?;
+        Ok(read[0] != COMMAND_ESD_CHECK && read[1] == COMMAND_ESD_CHECK)
+    }
+
+    /// Bench feature: reads all five touch point slots (8 bytes each, 0x814F-0x8176) regardless of the touch count
+    /// Shows what the controller leaves in the inactive slots (e.g. whether it clears them on release)
+    /// Decode the active slots with get_multi_touch, the status register is neither checked nor cleared here
+    pub fn read_all_slots_raw(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8; ALL_SLOTS_LEN],
+    ) -> Result<(), E> {
+        self.read(i2c, self.registers.touchpoint_1, buf)
+    }
+
+    /// Go / no-go check of the controller for an end-of-line test fixture, see SelfTestReport for the checks
+    /// Reads the product info and the config block along with its checksum, nothing is written
+    /// Err is only returned when the bus fails, a failed check is reported in the SelfTestReport
+    pub fn self_test(&self, i2c: &mut I2C) -> Result<SelfTestReport, E> {
+        let mut product_info = [0u8; PRODUCT_INFO_LEN];
+        self.read(i2c, self.registers.product_id, &mut product_info)?;
+        let mut config = [0u8; CONFIG_LEN + 1];
+        self.read(i2c, GT911_CONFIG_START_REG, &mut config)?;
+        Ok(decode_self_test(&product_info, &config))
+    }
+
+    /// Reads the coordinate stretch config, see StretchConfig
+    pub fn read_stretch(&self, i2c: &mut I2C) -> Result<StretchConfig, E> {
+        let mut read = [0u8; 4];
+        self.read(i2c, GT911_STRETCH_R0_REG, &mut read)?;
+        Ok(decode_stretch(&read))
+    }
+
+    /// Sets the coordinate stretch config, see StretchConfig
+    pub fn set_stretch(&self, i2c: &mut I2C, stretch: &StretchConfig) -> Result<(), E> {
+        self.update_config(i2c, |config| {
+            let offset = config_offset(GT911_STRETCH_R0_REG);
+            encode_stretch(stretch, &mut config[offset..offset + 4]);
+        })
+    }
+
+    /// Reads the frequency hopping config, see FrequencyHopping
+    pub fn read_frequency_hopping(&self, i2c: &mut I2C) -> Result<FrequencyHopping, E> {
+        let mut read = [0u8; 4];
+        self.read(i2c, GT911_FREQ_HOPPING_START_REG, &mut read)?;
+        Ok(decode_frequency_hopping(&read))
+    }
+
+    /// Sets the frequency hopping config, see FrequencyHopping. start must not be above end
+    pub fn set_frequency_hopping(
+        &self,
+        i2c: &mut I2C,
+        hopping: &FrequencyHopping,
+    ) -> Result<(), E> {
+        assert!(hopping.start <= hopping.end);
+        self.update_config(i2c, |config| {
+            let offset = config_offset(GT911_FREQ_HOPPING_START_REG);
+            encode_frequency_hopping(hopping, &mut config[offset..offset + 4]);
+        })
+    }
+
+    /// Gets a single touch point from a frame that is guaranteed to be consistent, whatever with_torn_frame_check
+    /// is set to. The status register is read again after the touch point and the read is retried up to
+    /// max_retries times if it changed in-between. Returns Err(Error::TornFrame) if every attempt was torn
+    /// Returns Ok(None) for release, Some(point) for press or move and Err(Error::NotReady) for no data
+    pub fn get_touch_consistent(
+        &self,
+        i2c: &mut I2C,
+        max_retries: usize,
+    ) -> Result<Option<Point>, E> {
+        self.last_cleared.store(false, Ordering::Relaxed);
+        let (_, points) = self.read_status_and_points_checked(i2c, 1, Some(max_retries))?;
+
+        if self.auto_clear {
+            self.clear_status(i2c)?;
+        }
+        Ok(points.first().cloned())
+    }
+
+    // reads the status register followed by up to max_points touch points without clearing the status register
+    // with the torn frame check enabled the status register is read again afterwards and the whole read
+    // is retried if the controller updated the frame in the meantime
+    fn read_points(
+        &self,
+        i2c: &mut I2C,
+        max_points: usize,
+    ) -> Result<heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>, E> {
+        self.read_status_and_points(i2c, max_points)
+            .map(|(_, points)| points)
+    }
+
+    // same as read_points but also returns the raw status register of the frame
+    fn read_status_and_points(
+        &self,
+        i2c: &mut I2C,
+        max_points: usize,
+    ) -> Result<(u8, heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>), E> {
+        let max_retries = self.torn_frame_check.then_some(TORN_FRAME_RETRIES);
+        self.read_status_and_points_checked(i2c, max_points, max_retries)
+            .map_err(torn_frame_as_not_ready)
+    }
+
+    // re-reads the status register after the touch points when max_retries is set and starts over
+    // if it changed, Err(Error::TornFrame) once max_retries retries have all been torn
+    fn read_status_and_points_checked(
+        &self,
+        i2c: &mut I2C,
+        max_points: usize,
+        max_retries: Option<usize>,
+    ) -> Result<(u8, heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>), E> {
+        let mut read = [0u8; TOUCHPOINT_ENTRY_LEN * MAX_NUM_TOUCHPOINTS];
+        let (status, len) =
+            self.read_status_and_entries(i2c, &mut read, max_points, max_retries)?;
+        let mut points = heapless::Vec::new();
+        decode_points(
+            &read[..len],
+            self.reverse_slots,
+            self.offset,
+            &self.exclusion_zones,
+            &mut points,
+        )?;
+        Ok((status, points))
+    }
+
+    // same as read_status_and_points_checked but leaves the raw touch point entries in read
+    // returns the status register and the number of bytes of read holding entries
+    fn read_status_and_entries(
+        &self,
+        i2c: &mut I2C,
+        read: &mut [u8; TOUCHPOINT_ENTRY_LEN * MAX_NUM_TOUCHPOINTS],
+        max_points: usize,
+        max_retries: Option<usize>,
+    ) -> Result<(u8, usize), E> {
+        let mut retries = 0;
+        loop {
+            let status = self.read_status(i2c)?;
+            let len = decode_num_touch_points(status)?.min(max_points) * TOUCHPOINT_ENTRY_LEN;
+
+            // release frame (ready with zero touch points): never read the coordinate registers
+            // so that stale bytes left over from a previous touch can't be decoded as a phantom point
+            if len > 0 {
+                self.read(i2c, self.registers.touchpoint_1, &mut read[..len])?;
+            }
+
+            let Some(max_retries) = max_retries else {
+                return Ok((status, len));
+            };
+            if self.read_status(i2c)? == status {
+                return Ok((status, len));
+            }
+
+            retries += 1;
+            if retries > max_retries {
+                return Err(Error::TornFrame);
+            }
+        }
+    }
+
+    // reads the status register into buf[0] and the touch points into the rest of buf, in one transfer
+    // when the touch points directly follow the status register (always the case with the standard map)
+    fn read_frame(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<(), E> {
+        if self.registers.touchpoint_1 == self.registers.status + 1 {
+            self.read(i2c, self.registers.status, buf)
+        } else {
+            let (status, points) = buf.split_at_mut(1);
+            self.read(i2c, self.registers.status, status)?;
+            self.read(i2c, self.registers.touchpoint_1, points)
+        }
+    }
+
+    fn read_status(&self, i2c: &mut I2C) -> Result<u8, E> {
+        let mut read = [0u8; 1];
+        self.read(i2c, self.registers.status, &mut read)?;
+        Ok(read[0])
+    }
+
+    // reads the whole config block, lets f modify it and writes it back along with
+    // a recalculated checksum and the config fresh flag so that the controller applies it
+    fn update_config(&self, i2c: &mut I2C, f: impl FnOnce(&mut [u8])) -> Result<(), E> {
+        let mut buf = [0u8; CONFIG_BUF_SIZE];
+        self.read(i2c, GT911_CONFIG_START_REG, &mut buf[2..2 + CONFIG_LEN])?;
+        f(&mut buf[2..2 + CONFIG_LEN]);
+        prepare_config_write(&mut buf);
+        i2c.write(self.i2c_addr, &buf).map_err(Error::I2C)
+    }
+
+    fn write(&self, i2c: &mut I2C, register: u16, value: u8) -> Result<(), E> {
+        let register = register.to_be_bytes();
+        let cmd = [register[0], register[1], value];
+        i2c.write(self.i2c_addr, &cmd).map_err(Error::I2C)
+    }
+
+    fn read(&self, i2c: &mut I2C, register: u16, buf: &mut [u8]) -> Result<(), E> {
+        let register = register.to_be_bytes();
+        match self.read_mode {
+            ReadMode::Combined => i2c.write_read(self.i2c_addr, &register, buf),
+            ReadMode::Split => i2c
+                .write(self.i2c_addr, &register)
+                .and_then(|_| i2c.read(self.i2c_addr, buf)),
+            ReadMode::SplitWithDummy => {
+                i2c.write(self.i2c_addr, &register).and_then(|_| {
+                    // adjacent reads are one read on the bus, the first byte is thrown away
+                    let mut dummy = [0u8; 1];
+                    i2c.transaction(
+                        self.i2c_addr,
+                        &mut [Operation::Read(&mut dummy), Operation::Read(buf)],
+                    )
+                })
+            }
+        }
+        .map_err(Error::I2C)
+    }
+}
+
+/// Async Gt911
+pub struct Gt911<I2C> {
+    i2c_addr: u8, // e.g. 0x5D
+    sort_points: bool,
+    torn_frame_check: bool,
+    auto_clear: bool,
+    clear_on_init: bool,
+    require_command_write: bool,
+    reverse_slots: bool,
+    read_mode: ReadMode,
+    registers: RegisterMap,
+    // (x, y) added to every decoded point, see with_offset
+    offset: (i16, i16),
+    // touch points inside any of these are dropped, see with_exclusion_zones
+    exclusion_zones: heapless::Vec<ExclusionZone, MAX_EXCLUSION_ZONES>,
+    // whether the status register was cleared since the last read started, see last_cleared
+    last_cleared: AtomicBool,
+    i2c: PhantomData<I2C>,
+}
+
+/// Use the default I2C address for communication
+impl<I2C> Default for Gt911<I2C> {
+    fn default() -> Self {
+        Self::new(ADDR_5D)
+    }
+}
+
+impl<I2C> Gt911<I2C> {
+    /// Creates a new instance with a user specified 7-bit i2c address, normally ADDR_5D or ADDR_14
+    /// Debug builds panic on an address outside the valid 7-bit range (e.g. 0x5D << 1 by mistake)
+    pub fn new(i2c_addr: u8) -> Self {
+        debug_assert!(
+            (0x08..=0x77).contains(&i2c_addr),
+            "Invalid 7-bit i2c address, use ADDR_5D or ADDR_14"
+        );
+        Self {
+            i2c_addr,
+            sort_points: false,
+            torn_frame_check: false,
+            auto_clear: true,
+            clear_on_init: true,
+            require_command_write: true,
+            reverse_slots: false,
+            read_mode: ReadMode::Combined,
+            registers: RegisterMap::default(),
+            offset: (0, 0),
+            exclusion_zones: heapless::Vec::new(),
+            last_cleared: AtomicBool::new(false),
+            i2c: PhantomData,
+        }
+    }
+
+    /// When enabled, points returned by get_multi_touch are sorted by track_id (ascending)
+    /// The controller does not guarantee any slot order so this gives a stable finger order frame-to-frame
+    pub fn with_sorted_points(mut self, sort_points: bool) -> Self {
+        self.sort_points = sort_points;
+        self
+    }
+
+    /// When enabled, the status register is read again after the touch points and the read is retried
+    /// if it changed in-between (the controller updated the frame mid-read). This guards against torn frames
+    /// at the cost of an extra transaction per read. Err(Error::NotReady) is returned if the frame keeps changing
+    pub fn with_torn_frame_check(mut self, torn_frame_check: bool) -> Self {
+        self.torn_frame_check = torn_frame_check;
+        self
+    }
+
+    /// When enabled (the default), get_touch and get_multi_touch clear the status register after reading
+    /// When disabled the frame stays ready so that several reads (e.g. touch points and keys) can be made against it,
+    /// call clear_status once they are done to let the controller report the next frame
+    pub fn with_auto_clear(mut self, auto_clear: bool) -> Self {
+        self.auto_clear = auto_clear;
+        self
+    }
+
+    /// When enabled (the default), init clears the status register as its last step
+    /// Disable it in interrupt driven setups so that a touch that happened during boot is not lost
+    /// and the first read after init still sees the pending frame
+    pub fn with_clear_on_init(mut self, clear_on_init: bool) -> Self {
+        self.clear_on_init = clear_on_init;
+        self
+    }
+
+    /// When enabled (the default), init fails if the write that puts the controller into coordinate mode fails
+    /// Disable it for boards where that write occasionally NAKs on a healthy controller, init then ignores
+    /// the failure and goes on to the product id check, which still catches a controller that is not there
+    pub fn with_require_command_write(mut self, require_command_write: bool) -> Self {
+        self.require_command_write = require_command_write;
+        self
+    }
+
+    /// When enabled, multi-touch reads return the touch point slots in reverse order (last reported slot first)
+    /// For panels that fill the slots in reverse so that the primary finger consistently ends up at index 0
+    /// Only the order changes, get_touch still reads the first slot. with_sorted_points takes precedence
+    pub fn with_reverse_slots(mut self, reverse_slots: bool) -> Self {
+        self.reverse_slots = reverse_slots;
+        self
+    }
+
+    /// Selects how registers are read (ReadMode::Combined by default)
+    /// Only change this for clones that don't handle a combined write_read (repeated start) correctly
+    pub fn with_read_mode(mut self, read_mode: ReadMode) -> Self {
+        self.read_mode = read_mode;
+        self
+    }
+
+    /// Overrides the registers used to read touch data (RegisterMap::default(), the standard map, by default)
+    /// For custom firmware that remaps the command, product id, status or touch point registers
+    pub fn with_register_map(mut self, registers: RegisterMap) -> Self {
+        self.registers = registers;
+        self
+    }
+
+    /// Whether the last get_touch, get_multi_touch, get_touches or get_primary_touch call (or a clear_status since) cleared
+    /// the status register. False after a read that returned an error (e.g. Err(Error::NotReady)) or when
+    /// auto clear is disabled, useful to see why a following read returns Err(Error::NotReady) or the same frame again
+    pub fn last_cleared(&self) -> bool {
+        self.last_cleared.load(Ordering::Relaxed)
+    }
+
+    /// Adds a constant offset to the x and y of every touch point read (0, 0 by default)
+    /// A lightweight fix for a panel mounted a few pixels off, the result is clamped to 0..=u16::MAX
+    pub fn with_offset(mut self, x_offset: i16, y_offset: i16) -> Self {
+        self.offset = (x_offset, y_offset);
+        self
+    }
+
+    /// The (x, y) offset added to every touch point read, see with_offset
+    pub fn offset(&self) -> (i16, i16) {
+        self.offset
+    }
+
+    /// Drops every touch point that falls inside any of zones (none by default), e.g. to mask a bezel that
+    /// overlaps the active area on several edges. Points are checked after with_offset is applied.
+    /// The remaining points keep their order, a frame where every point was dropped reads like a release frame
+    /// The zones are copied onto the driver so they can be computed at runtime (e.g. from read_resolution)
+    /// Panics if there are more than MAX_EXCLUSION_ZONES zones
+    pub fn with_exclusion_zones(mut self, zones: &[ExclusionZone]) -> Self {
+        assert!(
+            zones.len() <= MAX_EXCLUSION_ZONES,
+            "Too many exclusion zones, at most MAX_EXCLUSION_ZONES"
+        );
+        self.exclusion_zones = zones.iter().copied().collect();
+        self
+    }
+}
+
+/// Async Gt911 implementation
+impl<I2C, E> Gt911<I2C>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    /// Checks the ProductId for a "911\0" string response and resets the status register (see with_clear_on_init)
+    /// Only needs to be called once on startup
+    /// buf is a temp read buffer and should be at least 4 bytes in length
+    pub async fn init(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<(), E> {
         // switch to command mode
-        self.write(i2c, GT911_COMMAND_REG, 0).await?;
+        let written = self
+            .write(i2c, self.registers.command, COMMAND_READ_COORDINATES)
+            .await;
+        if self.require_command_write {
+            written?;
+        }
+
+        // read the product_id and confirm that it is expected
+        const LEN: usize = 4;
+        assert!(buf.len() >= LEN);
+        self.read(i2c, self.registers.product_id, &mut buf[..LEN])
+            .await?;
+        match str::from_utf8(&buf[..LEN]) {
+            Ok(product_id) => {
+                if product_id != "911\0" {
+                    return Err(Error::UnexpectedProductId);
+                }
+            }
+            Err(_) => {
+                return Err(Error::UnexpectedProductId);
+            }
+        }
+
+        // clear status register
+        if self.clear_on_init {
+            self.write(i2c, self.registers.status, 0).await?;
+        }
+        Ok(())
+    }
+
+    /// Gets a single touch point
+    /// Returns Ok(None) for release, Some(point) for press or move and Err(Error::NotReady) for no data
+    /// The status register is polled on its own, once it reports a touch the status register and the first touch
+    /// point are read in a single transfer into buf which keeps the coordinates and their status consistent
+    /// Cancel safe: the status register is only cleared after everything has been read so if the future is
+    /// dropped (e.g. by a select! timeout) the frame is left unconsumed and returned again by the next call
+    /// buf is a temp read buffer and should be at least GET_TOUCH_BUF_SIZE (9) bytes in length
+    pub async fn get_touch(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<Option<Point>, E> {
+        assert!(
+            buf.len() >= GET_TOUCH_BUF_SIZE,
+            "Buffer too small, use GET_TOUCH_BUF_SIZE"
+        );
+        self.last_cleared.store(false, Ordering::Relaxed);
+        let points = self.read_points(i2c, buf, 1).await?;
+
+        if self.auto_clear {
+            self.clear_status(i2c).await?;
+        }
+        Ok(points.first().cloned())
+    }
+
+    /// Gets a single touch point along with the large touch (palm) flag of the same frame
+    /// Returns point None for release, Some(point) for press or move and Err(Error::NotReady) for no data
+    /// buf is a temp read buffer and should be at least GET_TOUCH_BUF_SIZE (9) bytes in length
+    pub async fn get_touch_result(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<TouchResult, E> {
+        assert!(
+            buf.len() >= GET_TOUCH_BUF_SIZE,
+            "Buffer too small, use GET_TOUCH_BUF_SIZE"
+        );
+        self.last_cleared.store(false, Ordering::Relaxed);
+        let (status, points) = self.read_status_and_points(i2c, buf, 1).await?;
+
+        if self.auto_clear {
+            self.clear_status(i2c).await?;
+        }
+        Ok(TouchResult {
+            point: points.first().cloned(),
+            large_touch: decode_touch_status(status)?.large_detect,
+        })
+    }
+
+    /// Gets multiple stack allocated touch points (0-5 points)
+    /// Returns points.len()==0 for release, points.len()>0 for press or move and Err(Error::NotReady) for no data
+    /// The status register is polled on its own, once it reports touches the status register and the touch
+    /// points are read in a single transfer into buf which keeps the coordinates and their status consistent
+    /// Cancel safe: the status register is only cleared after everything has been read so if the future is
+    /// dropped (e.g. by a select! timeout) the frame is left unconsumed and returned again by the next call
+    /// buf is a temp read buffer and should be at least GET_MULTITOUCH_BUF_SIZE (41) bytes in length
+    pub async fn get_multi_touch(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+    ) -> Result<heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>, E> {
+        assert!(
+            buf.len() >= GET_MULTITOUCH_BUF_SIZE,
+            "Buffer too small, use GET_MULTITOUCH_BUF_SIZE"
+        );
+        self.last_cleared.store(false, Ordering::Relaxed);
+        let mut points = self.read_points(i2c, buf, MAX_NUM_TOUCHPOINTS).await?;
+
+        if self.sort_points {
+            points.sort_unstable_by_key(|point| point.track_id);
+        }
+
+        if self.auto_clear {
+            self.clear_status(i2c).await?;
+        }
+        Ok(points)
+    }
+
+    /// Polls several devices sharing the same bus in one pass (e.g. two panels at 0x5D and 0x14)
+    /// f is called with the index (into touches) of every device that has new data along with the result of
+    /// its get_multi_touch call. Devices that return Err(Error::NotReady) are skipped
+    /// buf is a temp read buffer shared by all devices and should be at least GET_MULTITOUCH_BUF_SIZE bytes in length
+    pub async fn poll_many(
+        touches: &[Self],
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        mut f: impl FnMut(usize, Result<heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>, E>),
+    ) {
+        for (index, touch) in touches.iter().enumerate() {
+            match touch.get_multi_touch(i2c, buf).await {
+                Err(Error::NotReady) => {}
+                result => f(index, result),
+            }
+        }
+    }
+
+    /// Sets the number of seconds without a touch before the controller drops into its low power (green) mode
+    /// seconds must be 0-15. Lower values save power at the cost of a slower first touch after idling
+    /// The controller does not report when it enters or leaves this mode, see Power management in the README
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn set_idle_timeout(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        seconds: u8,
+    ) -> Result<(), E> {
+        assert!(seconds <= 0x0F);
+        self.update_config(i2c, buf, |config| {
+            let reg = &mut config[config_offset(GT911_LOW_POWER_CONTROL_REG)];
+            *reg = (*reg & 0xF0) | seconds;
+        })
+        .await
+    }
+
+    /// Reads the number of seconds without a touch before the controller enters low power (green) mode
+    /// buf is a temp read buffer and should be at least 1 byte in length
+    pub async fn read_idle_timeout(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<u8, E> {
+        assert!(!buf.is_empty());
+        self.read(i2c, GT911_LOW_POWER_CONTROL_REG, &mut buf[..1])
+            .await?;
+        Ok(buf[0] & 0x0F)
+    }
+
+    /// Sets the coordinate report (scan) period to 5 + period milliseconds (low nibble of Refresh_Rate, 0x8056)
+    /// period must be 0-15. Longer periods reduce power consumption and interrupt load
+    /// This is the normal mode rate, the GT911 has no separate green (low power) mode rate register
+    /// (0x8057 is X_Threshold), green mode is only entered after set_idle_timeout seconds without a touch
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn set_refresh_rate(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        period: u8,
+    ) -> Result<(), E> {
+        assert!(period <= 0x0F);
+        self.update_config(i2c, buf, |config| {
+            let reg = &mut config[config_offset(GT911_REFRESH_RATE_REG)];
+            *reg = (*reg & 0xF0) | period;
+        })
+        .await
+    }
+
+    /// Reads the normal mode coordinate report (scan) period, the actual period is 5 + period milliseconds
+    /// buf is a temp read buffer and should be at least 1 byte in length
+    pub async fn read_refresh_rate(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<u8, E> {
+        assert!(!buf.is_empty());
+        self.read(i2c, GT911_REFRESH_RATE_REG, &mut buf[..1])
+            .await?;
+        Ok(buf[0] & 0x0F)
+    }
+
+    /// Enables or disables swapping of the x and y axes by the controller itself (X2Y bit of Module_Switch1)
+    /// Useful for landscape mounted panels because no per-point transform is needed in software
+    /// Note that the configured x and y output maximums are not swapped along with the axes
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn set_swap_xy(&self, i2c: &mut I2C, buf: &mut [u8], enabled: bool) -> Result<(), E> {
+        self.update_config(i2c, buf, |config| {
+            let reg = &mut config[config_offset(GT911_MODULE_SWITCH_1_REG)];
+            *reg = (*reg & !MODULE_SWITCH_1_X2Y) | if enabled { MODULE_SWITCH_1_X2Y } else { 0 };
+        })
+        .await
+    }
+
+    /// Reads whether the controller is configured to swap the x and y axes
+    /// buf is a temp read buffer and should be at least 1 byte in length
+    pub async fn read_swap_xy(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<bool, E> {
+        assert!(!buf.is_empty());
+        self.read(i2c, GT911_MODULE_SWITCH_1_REG, &mut buf[..1])
+            .await?;
+        Ok(buf[0] & MODULE_SWITCH_1_X2Y != 0)
+    }
+
+    /// Reads the config block (0x8047-0x80FE) into config which should be at least CONFIG_LEN bytes in length
+    /// The checksum and config fresh flag that follow the block are not included
+    pub async fn read_config(&self, i2c: &mut I2C, config: &mut [u8]) -> Result<(), E> {
+        assert!(
+            config.len() >= CONFIG_LEN,
+            "Buffer too small, use CONFIG_LEN"
+        );
+        self.read(i2c, GT911_CONFIG_START_REG, &mut config[..CONFIG_LEN])
+            .await
+    }
+
+    /// Writes the config block (0x8047-0x80FE) along with its checksum and sets the config fresh flag
+    /// so that the controller applies it. config should be at least CONFIG_LEN bytes in length
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    /// NOTE: the controller stores the config in flash so avoid writing it repeatedly, see ensure_config
+    pub async fn write_config(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        config: &[u8],
+    ) -> Result<(), E> {
+        assert!(
+            config.len() >= CONFIG_LEN,
+            "Buffer too small, use CONFIG_LEN"
+        );
+        assert!(
+            buf.len() >= CONFIG_BUF_SIZE,
+            "Buffer too small, use CONFIG_BUF_SIZE"
+        );
+        let buf = &mut buf[..CONFIG_BUF_SIZE];
+        buf[2..2 + CONFIG_LEN].copy_from_slice(&config[..CONFIG_LEN]);
+        prepare_config_write(buf);
+        i2c.write(self.i2c_addr, buf).await.map_err(Error::I2C)
+    }
+
+    /// Writes the desired config only if it differs from the config currently on the controller
+    /// Returns true if a write occurred. Use this on every startup to avoid needless flash wear
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn ensure_config(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        desired: &[u8],
+    ) -> Result<bool, E> {
+        assert!(
+            desired.len() >= CONFIG_LEN,
+            "Buffer too small, use CONFIG_LEN"
+        );
+        assert!(
+            buf.len() >= CONFIG_BUF_SIZE,
+            "Buffer too small, use CONFIG_BUF_SIZE"
+        );
+        self.read_config(i2c, &mut buf[2..2 + CONFIG_LEN]).await?;
+        if buf[2..2 + CONFIG_LEN] == desired[..CONFIG_LEN] {
+            return Ok(false);
+        }
+
+        self.write_config(i2c, buf, desired).await?;
+        Ok(true)
+    }
+
+    /// Waits for a falling edge on the INT pin and then reads a single touch point
+    /// Returns Ok(None) for release and Some(point) for press or move. Never returns Err(Error::NotReady)
+    /// If new data is already waiting when this is called (INT already asserted) it is returned immediately
+    /// without waiting for an edge. int should be configured as an input connected to the GT911 INT line
+    /// Cancel safe for the same reason as get_touch
+    /// buf is a temp read buffer and should be at least GET_TOUCH_BUF_SIZE bytes in length
+    pub async fn wait_for_touch(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        int: &mut impl embedded_hal_async::digital::Wait,
+    ) -> Result<Option<Point>, E> {
+        loop {
+            match self.get_touch(i2c, buf).await {
+                Err(Error::NotReady) => {
+                    int.wait_for_falling_edge().await.map_err(|_| Error::Pin)?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Releases an INT line that is stuck asserted
+    /// The GT911 keeps INT asserted (and stops reporting new touch data) until the status register is cleared.
+    /// If a read is skipped or aborted before the clear (e.g. a bug elsewhere or a cancelled future) an edge
+    /// triggered interrupt handler will never fire again. This switches back to coordinate reading mode and clears
+    /// the status register without reading anything. Call init again if the controller still does not respond
+    pub async fn recover(&self, i2c: &mut I2C) -> Result<(), E> {
+        self.resume_reading(i2c).await
+    }
+
+    /// Reads the number of drive and sense lines configured for the sensor (config 0x8062-0x8064)
+    /// Useful to sanity check that you are talking to the expected module or to validate a config before writing it
+    /// buf is a temp read buffer and should be at least 3 bytes in length
+    pub async fn read_sensor_geometry(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+    ) -> Result<SensorGeometry, E> {
+        const LEN: usize = 3;
+        assert!(buf.len() >= LEN);
+        self.read(i2c, GT911_DRIVER_GROUP_A_REG, &mut buf[..LEN])
+            .await?;
+        Ok(decode_sensor_geometry(buf))
+    }
+
+    /// Sets the x and y output resolution (X_Output_Max and Y_Output_Max in the config)
+    /// Reported coordinates are scaled by the controller to fit this resolution
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn set_resolution(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        width: u16,
+        height: u16,
+    ) -> Result<(), E> {
+        self.update_config(i2c, buf, |config| {
+            let offset = config_offset(GT911_X_OUTPUT_MAX_REG);
+            config[offset..offset + 2].copy_from_slice(&width.to_le_bytes());
+            config[offset + 2..offset + 4].copy_from_slice(&height.to_le_bytes());
+        })
+        .await
+    }
+
+    /// Reads the configured x and y output resolution as (width, height)
+    /// buf is a temp read buffer and should be at least 4 bytes in length
+    pub async fn read_resolution(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<(u16, u16), E> {
+        const LEN: usize = 4;
+        assert!(buf.len() >= LEN);
+        self.read(i2c, GT911_X_OUTPUT_MAX_REG, &mut buf[..LEN])
+            .await?;
+        Ok(decode_resolution(buf))
+    }
+
+    /// Checks whether anything acknowledges the i2c address using a single byte read
+    /// Returns Ok(false) if the address is not acknowledged. This is much cheaper than init because
+    /// it does not switch modes or validate the product id so it is suitable for hot-plug detection
+    /// buf is a temp read buffer and should be at least 1 byte in length
+    pub async fn is_present(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<bool, E>
+    where
+        E: embedded_hal::i2c::Error,
+    {
+        assert!(!buf.is_empty());
+        match i2c.read(self.i2c_addr, &mut buf[..1]).await {
+            Ok(()) => Ok(true),
+            Err(e) if matches!(e.kind(), ErrorKind::NoAcknowledge(_)) => Ok(false),
+            Err(e) => Err(Error::I2C(e)),
+        }
+    }
+
+    /// Reads the touch key configuration (key positions, thresholds and sensitivities at 0x8093-0x809B)
+    /// buf is a temp read buffer and should be at least 9 bytes in length
+    pub async fn read_touch_keys(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+    ) -> Result<TouchKeyConfig, E> {
+        assert!(buf.len() >= TOUCH_KEY_CONFIG_LEN);
+        self.read(i2c, GT911_KEY_1_REG, &mut buf[..TOUCH_KEY_CONFIG_LEN])
+            .await?;
+        Ok(decode_touch_keys(buf))
+    }
+
+    /// Writes the touch key configuration with checksum refresh
+    /// Use this to tune the key sensitivity independently from the main panel touch thresholds
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn set_touch_keys(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        keys: &TouchKeyConfig,
+    ) -> Result<(), E> {
+        self.update_config(i2c, buf, |config| {
+            let offset = config_offset(GT911_KEY_1_REG);
+            encode_touch_keys(keys, &mut config[offset..offset + TOUCH_KEY_CONFIG_LEN]);
+        })
+        .await
+    }
+
+    /// Resets the controller and selects a new i2c address (0x5D or 0x14) returning a driver bound to it
+    /// The GT911 has no address register, the address is latched from the INT pin level when RESET is released.
+    /// This drives both pins through the datasheet power-on sequence: RESET low, INT set to select the address,
+    /// RESET high, then INT held low for 50ms. Afterwards the caller MUST switch int back to a floating input
+    /// so that the controller can drive it, and then call init on the returned driver
+    /// NOT cancel safe: dropping the future mid-sequence can leave the controller held in reset or latched
+    /// to an undefined address, run it to completion (no select! or timeout around it)
+    pub async fn change_address(
+        self,
+        rst: &mut impl embedded_hal::digital::OutputPin,
+        int: &mut impl embedded_hal::digital::OutputPin,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+        i2c_addr: u8,
+    ) -> Result<Self, E> {
+        assert!(i2c_addr == ADDR_5D || i2c_addr == ADDR_14);
+        rst.set_low().map_err(|_| Error::Pin)?;
+        delay.delay_ms(20).await;
+        int.set_state((i2c_addr == ADDR_14).into())
+            .map_err(|_| Error::Pin)?;
+        delay.delay_ms(2).await;
+        rst.set_high().map_err(|_| Error::Pin)?;
+        delay.delay_ms(6).await;
+        int.set_low().map_err(|_| Error::Pin)?;
+        delay.delay_ms(50).await;
+        Ok(Self { i2c_addr, ..self })
+    }
+
+    /// Writes the config block like write_config and then reads back the checksum register
+    /// Returns Err(Error::ConfigChecksum) if it does not match the checksum calculated for config
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn write_config_verified(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        config: &[u8],
+    ) -> Result<(), E> {
+        self.write_config(i2c, buf, config).await?;
+        self.read(i2c, GT911_CONFIG_CHECKSUM_REG, &mut buf[..1])
+            .await?;
+        if buf[0] != config_checksum(&config[..CONFIG_LEN]) {
+            return Err(Error::ConfigChecksum);
+        }
+        Ok(())
+    }
+
+    /// Reads the config checksum (0x80FF) and config fresh flag (0x8100) in one read as (checksum, fresh_flag)
+    /// The controller clears the fresh flag once it has applied a new config so this confirms a config write
+    /// buf is a temp read buffer and should be at least 2 bytes in length
+    pub async fn read_config_status(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<(u8, u8), E> {
+        const LEN: usize = 2;
+        assert!(buf.len() >= LEN);
+        self.read(i2c, GT911_CONFIG_CHECKSUM_REG, &mut buf[..LEN])
+            .await?;
+        Ok((buf[0], buf[1]))
+    }
+
+    /// Switches the controller back to reading coordinates (writes 0 to the command register) and clears
+    /// the status register. Use this after config or diagnostic operations that leave the controller in a
+    /// different command mode, without having to go through a full init
+    pub async fn resume_reading(&self, i2c: &mut I2C) -> Result<(), E> {
+        self.write(i2c, self.registers.command, COMMAND_READ_COORDINATES)
+            .await?;
+        self.write(i2c, self.registers.status, 0).await
+    }
+
+    /// Reads everything needed to confirm the part and its config in one call (board bring-up diagnostics)
+    /// Unlike init this does not validate the product id or change the controller mode
+    /// buf is a temp read buffer and should be at least 11 bytes in length
+    pub async fn probe(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<ProbeInfo, E> {
+        assert!(buf.len() >= PRODUCT_INFO_LEN);
+        self.read(i2c, GT911_CONFIG_START_REG, &mut buf[..1])
+            .await?;
+        let config_version = buf[0];
+        self.read(i2c, self.registers.product_id, &mut buf[..PRODUCT_INFO_LEN])
+            .await?;
+        Ok(decode_probe_info(buf, config_version))
+    }
+
+    /// Reads the raw 8 byte entry of touch point slot (0-4) without decoding it or touching the status register
+    /// Layout: track_id, x (le u16), y (le u16), area (le u16), reserved
+    pub async fn read_touchpoint_raw(
+        &self,
+        i2c: &mut I2C,
+        slot: u8,
+        buf: &mut [u8; TOUCHPOINT_ENTRY_LEN],
+    ) -> Result<(), E> {
+        self.read(i2c, touchpoint_reg(self.registers.touchpoint_1, slot), buf)
+            .await
+    }
+
+    /// Sets the strength of the controller's built-in coordinate filter (Normal_Filter, bits 5-0 of config 0x8050)
+    /// strength must be 0-63, the filter window is strength * 4. Higher values smooth out jitter at the cost
+    /// of responsiveness to small movements. The First_Filter bits are left unchanged
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn set_coordinate_filter(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        strength: u8,
+    ) -> Result<(), E> {
+        assert!(strength <= FILTER_NORMAL_MASK);
+        self.update_config(i2c, buf, |config| {
+            let reg = &mut config[config_offset(GT911_FILTER_REG)];
+            *reg = (*reg & !FILTER_NORMAL_MASK) | strength;
+        })
+        .await
+    }
+
+    /// Reads the strength of the controller's built-in coordinate filter (0-63)
+    /// buf is a temp read buffer and should be at least 1 byte in length
+    pub async fn read_coordinate_filter(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<u8, E> {
+        assert!(!buf.is_empty());
+        self.read(i2c, GT911_FILTER_REG, &mut buf[..1]).await?;
+        Ok(buf[0] & FILTER_NORMAL_MASK)
+    }
+
+    /// Sets the maximum number of touch points the controller scans for and reports (Touch_Number, 1-5)
+    /// Setting this to 1 stops the controller from tracking extra fingers (e.g. a resting palm) which
+    /// saves power and bus traffic compared to discarding the extra points in software
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn set_max_touches(&self, i2c: &mut I2C, buf: &mut [u8], n: u8) -> Result<(), E> {
+        assert!((1..=MAX_NUM_TOUCHPOINTS as u8).contains(&n));
+        self.update_config(i2c, buf, |config| {
+            let reg = &mut config[config_offset(GT911_TOUCH_NUMBER_REG)];
+            *reg = (*reg & 0xF0) | n;
+        })
+        .await
+    }
+
+    /// Reads the maximum number of touch points the controller scans for (Touch_Number)
+    /// buf is a temp read buffer and should be at least 1 byte in length
+    pub async fn read_max_touches(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<u8, E> {
+        assert!(!buf.is_empty());
+        self.read(i2c, GT911_TOUCH_NUMBER_REG, &mut buf[..1])
+            .await?;
+        Ok(buf[0] & 0x0F)
+    }
+
+    /// Sets the width of the INT pulse sent for each touch report (upper nibble of Refresh_Rate, 0x8056)
+    /// width must be 0-15. Widen it if the MCU misses short edges when edge triggered
+    /// Note that 0x80D5 (mentioned in some vendor notes) is a driver channel map entry, not an interrupt timing setting
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn set_interrupt_pulse_width(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        width: u8,
+    ) -> Result<(), E> {
+        assert!(width <= 0x0F);
+        self.update_config(i2c, buf, |config| {
+            let reg = &mut config[config_offset(GT911_REFRESH_RATE_REG)];
+            *reg = (*reg & 0x0F) | (width << 4);
+        })
+        .await
+    }
+
+    /// Reads the configured width of the INT pulse sent for each touch report (0-15)
+    /// buf is a temp read buffer and should be at least 1 byte in length
+    pub async fn read_interrupt_pulse_width(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<u8, E> {
+        assert!(!buf.is_empty());
+        self.read(i2c, GT911_REFRESH_RATE_REG, &mut buf[..1])
+            .await?;
+        Ok(buf[0] >> 4)
+    }
+
+    /// Reads the gesture register (0x814B) and clears it so that each gesture is returned exactly once
+    /// Returns Ok(None) if no gesture is latched. The gesture code stays latched until cleared so
+    /// reading it without clearing reports the same gesture over and over
+    /// Only gesture enabled firmware reports gestures, the touch status register is not touched
+    /// buf is a temp read buffer and should be at least 1 byte in length
+    pub async fn take_gesture(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<Option<Gesture>, E> {
+        assert!(!buf.is_empty());
+        self.read(i2c, GT911_GESTURE_REG, &mut buf[..1]).await?;
+        let gesture = decode_gesture(buf[0]);
+        if gesture.is_some() {
+            self.write(i2c, GT911_GESTURE_REG, 0).await?;
+        }
+        Ok(gesture)
+    }
+
+    /// Expert / bench feature: reads the raw capacitance data of every sensor node into buf
+    /// Useful to find a dead or flaky sensor channel (e.g. a cracked trace reads far off its neighbours)
+    /// Each node is a big-endian u16, size buf as drive_lines * sense_lines * 2 (see read_sensor_geometry)
+    /// Switches the controller to raw data mode and returns Err(Error::NotReady) until a raw frame is available,
+    /// call it again until it succeeds. Once read the controller is put back into coordinate mode
+    /// Touch reporting is suspended while in raw data mode
+    /// NOT cancel safe: dropping the future after the mode switch leaves the controller in raw data mode,
+    /// call recover (or this again) to get back to coordinate mode
+    pub async fn read_raw_diagnostics(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<(), E> {
+        assert!(!buf.is_empty());
+        self.write(i2c, self.registers.command, COMMAND_READ_RAW_DATA)
+            .await?;
+        self.read(i2c, self.registers.status, &mut buf[..1]).await?;
+        decode_num_touch_points(buf[0])?;
+        self.read(i2c, GT911_RAW_DATA_REG, buf).await?;
+        self.write(i2c, self.registers.status, 0).await?;
+        self.write(i2c, self.registers.command, COMMAND_READ_COORDINATES)
+            .await
+    }
+
+    /// Clears the status register so the controller can report the next frame
+    /// Only needed when auto clear is disabled (see with_auto_clear)
+    pub async fn clear_status(&self, i2c: &mut I2C) -> Result<(), E> {
+        self.write(i2c, self.registers.status, 0).await?;
+        self.last_cleared.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Gets the first touch point with the least bus time, the same as get_touch which already reads the
+    /// status register and the first touch point in one transaction. Provided for parity with the blocking driver
+    /// buf is a temp read buffer and should be at least GET_TOUCH_BUF_SIZE (9) bytes in length
+    pub async fn get_primary_touch(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+    ) -> Result<Option<Point>, E> {
+        self.get_touch(i2c, buf).await
+    }
+
+    /// Reads the config block the controller is actually running with after a write_config
+    /// Returns Err(Error::NotReady) while the config fresh flag is still set (the new config has not been applied yet),
+    /// call it again until it succeeds. Diff the result against what was written to catch fields the controller
+    /// clamped or rejected. config should be at least CONFIG_LEN bytes in length
+    pub async fn read_effective_config(&self, i2c: &mut I2C, config: &mut [u8]) -> Result<(), E> {
+        assert!(
+            config.len() >= CONFIG_LEN,
+            "Buffer too small, use CONFIG_LEN"
+        );
+        self.read(i2c, GT911_CONFIG_FRESH_REG, &mut config[..1])
+            .await?;
+        if config[0] != 0 {
+            return Err(Error::NotReady);
+        }
+        self.read_config(i2c, config).await
+    }
+
+    /// Reads the config block (0x8047-0x80FE) in small chunks, each its own transaction, and calls f with each
+    /// chunk in order. Trades bus transactions for RAM when a CONFIG_LEN buffer can't be spared
+    /// buf is a temp read buffer, each chunk is up to buf.len() bytes long
+    pub async fn read_config_streaming(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        mut f: impl FnMut(&[u8]),
+    ) -> Result<(), E> {
+        let chunk_len = buf.len();
+        assert!(chunk_len > 0);
+        for offset in (0..CONFIG_LEN).step_by(chunk_len) {
+            let read = &mut buf[..(CONFIG_LEN - offset).min(chunk_len)];
+            self.read(i2c, GT911_CONFIG_START_REG + offset as u16, read)
+                .await?;
+            f(read);
+        }
+        Ok(())
+    }
+
+    /// Low level multi-touch read that calls f(track_id, x, y, area) for every touch point instead of returning them
+    /// so the caller can build whatever structure it wants. Returns the number of touch points (0 for release)
+    /// and Err(Error::NotReady) for no data. Sorting, torn frame check and auto clear are honored
+    /// buf is a temp read buffer and should be at least GET_MULTITOUCH_BUF_SIZE (41) bytes in length
+    pub async fn read_touches_with(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        mut f: impl FnMut(u8, u16, u16, u16),
+    ) -> Result<usize, E> {
+        assert!(
+            buf.len() >= GET_MULTITOUCH_BUF_SIZE,
+            "Buffer too small, use GET_MULTITOUCH_BUF_SIZE"
+        );
+        self.last_cleared.store(false, Ordering::Relaxed);
+        let max_retries = self.torn_frame_check.then_some(TORN_FRAME_RETRIES);
+        let (_, len) = self
+            .read_status_and_entries(i2c, buf, MAX_NUM_TOUCHPOINTS, max_retries)
+            .await
+            .map_err(torn_frame_as_not_ready)?;
+        let count = for_each_point(
+            &buf[1..1 + len],
+            self.reverse_slots,
+            self.sort_points,
+            self.offset,
+            &self.exclusion_zones,
+            |point| f(point.track_id, point.x, point.y, point.area),
+        )?;
+
+        if self.auto_clear {
+            self.clear_status(i2c).await?;
+        }
+        Ok(count)
+    }
+
+    /// Gets up to N stack allocated touch points, for callers that only need a few fingers (e.g. N = 2 for pinch)
+    /// If the controller reports more touch points than N only the first N slots are read and returned,
+    /// the extra touches are silently dropped and the status register is still cleared (if auto clear is on)
+    /// Returns points.len()==0 for release, points.len()>0 for press or move and Err(Error::NotReady) for no data
+    /// buf is a temp read buffer and should be at least buf_size(N) bytes in length
+    pub async fn get_touches<const N: usize>(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+    ) -> Result<heapless::Vec<Point, N>, E> {
+        let max_points = N.min(MAX_NUM_TOUCHPOINTS);
+        assert!(
+            buf.len() >= buf_size(max_points),
+            "Buffer too small, use buf_size(N)"
+        );
+        self.last_cleared.store(false, Ordering::Relaxed);
+        let points = self.read_points(i2c, buf, max_points).await?;
+        let mut points: heapless::Vec<Point, N> = points.into_iter().collect();
+
+        if self.sort_points {
+            points.sort_unstable_by_key(|point| point.track_id);
+        }
+
+        if self.auto_clear {
+            self.clear_status(i2c).await?;
+        }
+        Ok(points)
+    }
+
+    /// Discards any buffered touch frame so that the next read only reflects fresh input
+    /// Use it after waking from sleep or re-init to avoid acting on a stale frame (a phantom touch)
+    /// There is no need to read the coordinates first, clearing the status register drops the frame
+    pub async fn flush(&self, i2c: &mut I2C) -> Result<(), E> {
+        self.clear_status(i2c).await
+    }
+
+    /// Reads a single named field of the config block, see ConfigField for the available fields
+    /// buf is a temp read buffer and should be at least 2 bytes in length
+    pub async fn read_config_field(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        field: ConfigField,
+    ) -> Result<u16, E> {
+        assert!(buf.len() >= field.width());
+        let read = &mut buf[..field.width()];
+        self.read(i2c, field.register(), read).await?;
+        Ok(decode_config_field(read))
+    }
+
+    /// Writes a single named field of the config block, refreshing the checksum and setting the config fresh flag
+    /// value must fit the field (see ConfigField::max)
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn write_config_field(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        field: ConfigField,
+        value: u16,
+    ) -> Result<(), E> {
+        assert!(value <= field.max());
+        self.update_config(i2c, buf, |config| encode_config_field(field, value, config))
+            .await
+    }
+
+    /// Waits until the finger with track_id is lifted or timeout completes, whichever comes first
+    /// timeout is any future from your executor (e.g. embassy_time::Timer::after_millis(300)), which makes this
+    /// the building block for tap vs hold decisions. Frames are read with get_multi_touch whenever INT falls and
+    /// the finger counts as lifted once a frame arrives without its track_id. Call it while the finger is down
+    /// Cancel safe for the same reason as get_multi_touch, frames read while waiting are consumed
+    /// buf is a temp read buffer and should be at least GET_MULTITOUCH_BUF_SIZE (41) bytes in length
+    pub async fn wait_for_release(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        int: &mut impl embedded_hal_async::digital::Wait,
+        track_id: u8,
+        timeout: impl Future<Output = ()>,
+    ) -> Result<ReleaseOutcome, E> {
+        let released = async {
+            loop {
+                match self.get_multi_touch(i2c, buf).await {
+                    Err(Error::NotReady) => {}
+                    Err(e) => return Err(e),
+                    Ok(points) if !points.iter().any(|point| point.track_id == track_id) => {
+                        return Ok(ReleaseOutcome::Released);
+                    }
+                    // still held: wait for the next frame rather than re-reading this one (e.g. with auto clear off)
+                    Ok(_) => {}
+                }
+                int.wait_for_falling_edge().await.map_err(|_| Error::Pin)?;
+            }
+        };
 
-        // read the product_id and confirm that it is expected
-        const LEN: usize = 4;
+        with_timeout(released, timeout)
+            .await
+            .unwrap_or(Ok(ReleaseOutcome::TimedOut))
+    }
+
+    /// Recovery for a config write that broke touch: writes a known good config (e.g. a read_config dump taken
+    /// before experimenting) with its version byte set to 0 so the controller accepts it whatever config version
+    /// it is currently running (the version is then reset to 'A'). The GT911 keeps no factory copy of the config
+    /// that could be restored, so keep a backup. config should be at least CONFIG_LEN bytes in length
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn restore_config(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        config: &[u8],
+    ) -> Result<(), E> {
+        assert!(
+            config.len() >= CONFIG_LEN,
+            "Buffer too small, use CONFIG_LEN"
+        );
+        assert!(
+            buf.len() >= CONFIG_BUF_SIZE,
+            "Buffer too small, use CONFIG_BUF_SIZE"
+        );
+        let buf = &mut buf[..CONFIG_BUF_SIZE];
+        buf[2..2 + CONFIG_LEN].copy_from_slice(&config[..CONFIG_LEN]);
+        buf[2] = 0;
+        prepare_config_write(buf);
+        i2c.write(self.i2c_addr, buf).await.map_err(Error::I2C)
+    }
+
+    /// Bench feature: captures n consecutive frames into buf for offline jitter / noise analysis
+    /// Each frame is stored compactly as its status byte followed by its touch point entries (8 bytes each,
+    /// count in the low 4 bits of the status byte, at most 5). Busy polls until each frame is ready and always clears
+    /// the status register so keep a finger on the panel while capturing. Stops early once buf can't hold another
+    /// full frame (GET_MULTITOUCH_BUF_SIZE bytes). Returns the number of bytes of buf used
+    /// Returns Err(Error::Timeout) if a frame does not become ready within 10000 polls (e.g. no finger on the panel)
+    pub async fn capture_frames(
+        &self,
+        i2c: &mut I2C,
+        n: usize,
+        buf: &mut [u8],
+    ) -> Result<usize, E> {
+        let mut len = 0;
+        for _ in 0..n {
+            let frame = match buf.get_mut(len..len + GET_MULTITOUCH_BUF_SIZE) {
+                Some(frame) => frame,
+                None => break,
+            };
+            let mut polls = 0;
+            let num_touch_points = loop {
+                self.read_frame(i2c, frame).await?;
+                match decode_num_touch_points(frame[0]) {
+                    Ok(num_touch_points) => break num_touch_points,
+                    Err(Error::NotReady) if polls < CAPTURE_FRAME_POLLS => polls += 1,
+                    Err(Error::NotReady) => return Err(Error::Timeout),
+                    Err(e) => return Err(e),
+                }
+            };
+            self.clear_status(i2c).await?;
+            frame[0] = (frame[0] & 0xF0) | num_touch_points as u8;
+            len += buf_size(num_touch_points);
+        }
+        Ok(len)
+    }
+
+    /// Sets the number of drive and sense lines of the sensor (config 0x8062-0x8064), for authoring a config
+    /// for a custom panel. Driver groups must be 0-31 and sensor groups 0-15 lines. The channel maps that assign
+    /// the lines to controller pins are separate config fields and must match the panel too
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn set_sensor_geometry(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        geometry: &SensorGeometry,
+    ) -> Result<(), E> {
+        self.update_config(i2c, buf, |config| {
+            let offset = config_offset(GT911_DRIVER_GROUP_A_REG);
+            encode_sensor_geometry(geometry, &mut config[offset..offset + 3]);
+        })
+        .await
+    }
+
+    /// Reads the border space config, see BorderSpace
+    /// buf is a temp read buffer and should be at least 2 bytes in length
+    pub async fn read_border_space(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<BorderSpace, E> {
+        const LEN: usize = 2;
         assert!(buf.len() >= LEN);
-        self.read(i2c, GT911_PRODUCT_ID_REG, &mut buf[..LEN])
+        self.read(i2c, GT911_BORDER_SPACE_REG, &mut buf[..LEN])
             .await?;
-        match str::from_utf8(&buf[..LEN]) {
-            Ok(product_id) => {
-                if product_id != "911\0" {
-                    return Err(Error::UnexpectedProductId);
+        Ok(decode_border_space(&buf[..LEN]))
+    }
+
+    /// Sets the border space config, see BorderSpace. Each side must be 0-15
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn set_border_space(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        border: &BorderSpace,
+    ) -> Result<(), E> {
+        assert!(border.top <= 0x0F && border.bottom <= 0x0F);
+        assert!(border.left <= 0x0F && border.right <= 0x0F);
+        self.update_config(i2c, buf, |config| {
+            let offset = config_offset(GT911_BORDER_SPACE_REG);
+            encode_border_space(border, &mut config[offset..offset + 2]);
+        })
+        .await
+    }
+
+    /// Reads the complete firmware and config fingerprint of the controller for logging and support
+    /// buf is a temp read buffer and should be at least 11 bytes in length
+    pub async fn read_version_info(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<VersionInfo, E> {
+        assert!(buf.len() >= PRODUCT_INFO_LEN);
+        self.read(i2c, GT911_CONFIG_START_REG, &mut buf[..1])
+            .await?;
+        let config_version = buf[0];
+        self.read(i2c, GT911_CONFIG_CHECKSUM_REG, &mut buf[..1])
+            .await?;
+        let config_checksum = buf[0];
+        self.read(i2c, self.registers.product_id, &mut buf[..PRODUCT_INFO_LEN])
+            .await?;
+        Ok(decode_version_info(
+            &buf[..PRODUCT_INFO_LEN],
+            config_version,
+            config_checksum,
+        ))
+    }
+
+    /// Sets the minimum movement (X_Threshold and Y_Threshold, config 0x8057-0x8058) before the controller
+    /// reports a new position for a finger that is down. Smaller values give smoother drawing,
+    /// larger values fewer frames for a finger that is only jittering
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn set_move_threshold(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        x: u8,
+        y: u8,
+    ) -> Result<(), E> {
+        self.update_config(i2c, buf, |config| {
+            let offset = config_offset(GT911_X_THRESHOLD_REG);
+            config[offset] = x;
+            config[offset + 1] = y;
+        })
+        .await
+    }
+
+    /// Reads the minimum movement before a new position is reported as (x, y)
+    /// buf is a temp read buffer and should be at least 2 bytes in length
+    pub async fn read_move_threshold(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<(u8, u8), E> {
+        const LEN: usize = 2;
+        assert!(buf.len() >= LEN);
+        self.read(i2c, GT911_X_THRESHOLD_REG, &mut buf[..LEN])
+            .await?;
+        Ok((buf[0], buf[1]))
+    }
+
+    /// Re-calibrates the capacitance baseline the controller compares touches against (command 4)
+    /// Use this when the panel has drifted (e.g. with temperature or humidity) and touches become flaky,
+    /// instead of power cycling the controller. The panel must NOT be touched while this runs or the finger
+    /// becomes part of the baseline. There is no completion flag so this waits 200ms for the controller to
+    /// settle and then puts it back into coordinate mode. Discard the first frame or two read afterwards
+    /// NOT cancel safe: dropping the future during the wait leaves the controller out of coordinate mode,
+    /// call recover to get back to it
+    pub async fn recalibrate(
+        &self,
+        i2c: &mut I2C,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<(), E> {
+        self.write(i2c, self.registers.command, COMMAND_CALIBRATE_BASELINE)
+            .await?;
+        delay.delay_ms(CALIBRATE_SETTLE_MS).await;
+        self.write(i2c, self.registers.command, COMMAND_READ_COORDINATES)
+            .await
+    }
+
+    /// Checks whether the controller is running the touch application rather than sitting in its bootloader
+    /// Useful to decide between a reset and a reflash when init fails with Err(Error::UnexpectedProductId)
+    /// Writes the ESD check command (0xAA) to 0x8040 and waits 50ms, the application clears it and sets 0x8041
+    /// to 0xAA while the bootloader ignores it. Err(Error::I2C) means nothing acknowledged at the address at all
+    /// NOT cancel safe: dropping the future during the wait leaves the check command in the command register,
+    /// call recover to get back to coordinate mode
+    /// buf is a temp read buffer and should be at least 2 bytes in length
+    pub async fn is_in_application(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<bool, E> {
+        assert!(buf.len() >= 2);
+        self.write(i2c, self.registers.command + 1, 0).await?;
+        self.write(i2c, self.registers.command, COMMAND_ESD_CHECK)
+            .await?;
+        delay.delay_ms(COMMAND_SETTLE_MS).await;
+        self.read(i2c, self.registers.command, &mut buf[..2])
+            .await?;
+        Ok(buf[0] != COMMAND_ESD_CHECK && buf[1] == COMMAND_ESD_CHECK)
+    }
+
+    /// Bench feature: reads all five touch point slots (8 bytes each, 0x814F-0x8176) regardless of the touch count
+    /// Shows what the controller leaves in the inactive slots (e.g. whether it clears them on release)
+    /// Decode the active slots with get_multi_touch, the status register is neither checked nor cleared here
+    pub async fn read_all_slots_raw(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8; ALL_SLOTS_LEN],
+    ) -> Result<(), E> {
+        self.read(i2c, self.registers.touchpoint_1, buf).await
+    }
+
+    /// Go / no-go check of the controller for an end-of-line test fixture, see SelfTestReport for the checks
+    /// Reads the product info and the config block along with its checksum, nothing is written
+    /// Err is only returned when the bus fails, a failed check is reported in the SelfTestReport
+    /// buf is a temp read buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn self_test(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<SelfTestReport, E> {
+        assert!(
+            buf.len() >= CONFIG_BUF_SIZE,
+            "Buffer too small, use CONFIG_BUF_SIZE"
+        );
+        self.read(i2c, self.registers.product_id, &mut buf[..PRODUCT_INFO_LEN])
+            .await?;
+        let mut product_info = [0u8; PRODUCT_INFO_LEN];
+        product_info.copy_from_slice(&buf[..PRODUCT_INFO_LEN]);
+        self.read(i2c, GT911_CONFIG_START_REG, &mut buf[..CONFIG_LEN + 1])
+            .await?;
+        Ok(decode_self_test(&product_info, &buf[..CONFIG_LEN + 1]))
+    }
+
+    /// Waits until the number of fingers down changes (a finger added or lifted) and returns the new count
+    /// For apps that only care about fingers being added or removed, frames that only move fingers are skipped.
+    /// Only the status register is read and every frame is consumed (the status register is always cleared),
+    /// so use get_multi_touch afterwards if the points are needed. count is the caller's copy of the previous
+    /// count (start with 0) and is updated with every frame read, keep one per consumer
+    /// int should be configured as an input connected to the GT911 INT line
+    /// buf is a temp read buffer and should be at least 1 byte in length
+    pub async fn wait_for_count_change(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        int: &mut impl embedded_hal_async::digital::Wait,
+        count: &mut u8,
+    ) -> Result<u8, E> {
+        assert!(!buf.is_empty());
+        loop {
+            self.last_cleared.store(false, Ordering::Relaxed);
+            self.read(i2c, self.registers.status, &mut buf[..1]).await?;
+            match decode_num_touch_points(buf[0]) {
+                Err(Error::NotReady) => {
+                    int.wait_for_falling_edge().await.map_err(|_| Error::Pin)?;
+                }
+                Err(e) => return Err(e),
+                Ok(num_touch_points) => {
+                    self.clear_status(i2c).await?;
+                    let previous = core::mem::replace(count, num_touch_points as u8);
+                    if previous != *count {
+                        return Ok(*count);
+                    }
                 }
-            }
-            Err(_) => {
-                return Err(Error::UnexpectedProductId);
             }
         }
-
-        // clear status register
-        self.write(i2c, GT911_TOUCHPOINT_STATUS_REG, 0).await?;
-        Ok(())
     }
 
-    /// Gets a single touch point
-    /// Returns Ok(None) for release, Some(point) for press or move and Err(Error::NotReady) for no data
-    /// buf is a temp read buffer and should be at least 8 bytes in length
-    pub async fn get_touch(
+    /// Benchmarking helper: measures the time from INT falling to a completed get_multi_touch read
+    /// now is the caller's time source (e.g. a cycle counter or embassy_time::Instant::now().as_ticks()) and
+    /// the result is in its ticks, along with the frame that was read. Any pending frame is discarded first
+    /// (the status register is cleared) so that the measurement starts on a fresh edge. The time to wake up
+    /// on the edge is included, which is usually what matters when characterizing a system
+    /// buf is a temp read buffer and should be at least GET_MULTITOUCH_BUF_SIZE (41) bytes in length
+    pub async fn measure_read_latency(
         &self,
         i2c: &mut I2C,
         buf: &mut [u8],
-    ) -> Result<Option<Point>, Error<E>> {
-        let num_touch_points = self.get_num_touch_points(i2c, buf).await?;
+        int: &mut impl embedded_hal_async::digital::Wait,
+        mut now: impl FnMut() -> u64,
+    ) -> Result<(u64, heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>), E> {
+        self.clear_status(i2c).await?;
+        loop {
+            int.wait_for_falling_edge().await.map_err(|_| Error::Pin)?;
+            let start = now();
+            match self.get_multi_touch(i2c, buf).await {
+                Err(Error::NotReady) => {}
+                Err(e) => return Err(e),
+                Ok(points) => return Ok((now().wrapping_sub(start), points)),
+            }
+        }
+    }
 
-        let point = if num_touch_points > 0 {
-            assert!(
-                buf.len() >= TOUCHPOINT_ENTRY_LEN,
-                "Buffer too small, use GET_TOUCH_BUF_SIZE"
-            );
-            self.read(
-                i2c,
-                GT911_TOUCHPOINT_1_REG,
-                &mut buf[..TOUCHPOINT_ENTRY_LEN],
-            )
+    /// Reads the coordinate stretch config, see StretchConfig
+    /// buf is a temp read buffer and should be at least 4 bytes in length
+    pub async fn read_stretch(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<StretchConfig, E> {
+        const LEN: usize = 4;
+        assert!(buf.len() >= LEN);
+        self.read(i2c, GT911_STRETCH_R0_REG, &mut buf[..LEN])
             .await?;
-            let point = decode_point(buf);
-            Some(point)
-        } else {
-            None
-        };
+        Ok(decode_stretch(&buf[..LEN]))
+    }
 
-        // clear status register
-        self.write(i2c, GT911_TOUCHPOINT_STATUS_REG, 0).await?;
-        Ok(point)
+    /// Sets the coordinate stretch config, see StretchConfig
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn set_stretch(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        stretch: &StretchConfig,
+    ) -> Result<(), E> {
+        self.update_config(i2c, buf, |config| {
+            let offset = config_offset(GT911_STRETCH_R0_REG);
+            encode_stretch(stretch, &mut config[offset..offset + 4]);
+        })
+        .await
     }
 
-    /// Gets multiple stack allocated touch points (0-5 points)
-    /// Returns points.len()==0 for release, points.len()>0 for press or move and Err(Error::NotReady) for no data
-    /// buf is a temp read buffer and should be at least num_touch_points * 8 bytes in length (40 bytes to be safe because there can be up to 5 touch points)
-    pub async fn get_multi_touch(
+    /// Reads the frequency hopping config, see FrequencyHopping
+    /// buf is a temp read buffer and should be at least 4 bytes in length
+    pub async fn read_frequency_hopping(
         &self,
         i2c: &mut I2C,
         buf: &mut [u8],
-    ) -> Result<heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>, Error<E>> {
-        let num_touch_points = self.get_num_touch_points(i2c, buf).await?;
+    ) -> Result<FrequencyHopping, E> {
+        const LEN: usize = 4;
+        assert!(buf.len() >= LEN);
+        self.read(i2c, GT911_FREQ_HOPPING_START_REG, &mut buf[..LEN])
+            .await?;
+        Ok(decode_frequency_hopping(&buf[..LEN]))
+    }
 
-        let points = if num_touch_points > 0 {
-            assert!(num_touch_points <= MAX_NUM_TOUCHPOINTS);
-            let mut points = heapless::Vec::new();
+    /// Sets the frequency hopping config, see FrequencyHopping. start must not be above end
+    /// buf is a temp buffer and should be at least CONFIG_BUF_SIZE bytes in length
+    pub async fn set_frequency_hopping(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        hopping: &FrequencyHopping,
+    ) -> Result<(), E> {
+        assert!(hopping.start <= hopping.end);
+        self.update_config(i2c, buf, |config| {
+            let offset = config_offset(GT911_FREQ_HOPPING_START_REG);
+            encode_frequency_hopping(hopping, &mut config[offset..offset + 4]);
+        })
+        .await
+    }
 
-            // read touch points
-            let len: usize = num_touch_points * TOUCHPOINT_ENTRY_LEN;
-            assert!(
-                buf.len() >= len,
-                "Buffer too small, use GET_MULTITOUCH_BUF_SIZE"
-            );
-            self.read(i2c, GT911_TOUCHPOINT_1_REG, &mut buf[..len])
-                .await?;
+    /// Gets a single touch point from a frame that is guaranteed to be consistent, whatever with_torn_frame_check
+    /// is set to. The status register is read again after the touch point and the read is retried up to
+    /// max_retries times if it changed in-between. Returns Err(Error::TornFrame) if every attempt was torn
+    /// Returns Ok(None) for release, Some(point) for press or move and Err(Error::NotReady) for no data
+    /// buf is a temp read buffer and should be at least GET_TOUCH_BUF_SIZE (9) bytes in length
+    pub async fn get_touch_consistent(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        max_retries: usize,
+    ) -> Result<Option<Point>, E> {
+        assert!(
+            buf.len() >= GET_TOUCH_BUF_SIZE,
+            "Buffer too small, use GET_TOUCH_BUF_SIZE"
+        );
+        self.last_cleared.store(false, Ordering::Relaxed);
+        let (_, points) = self
+            .read_status_and_points_checked(i2c, buf, 1, Some(max_retries))
+            .await?;
 
-            for n in 0..num_touch_points {
-                let start = n * TOUCHPOINT_ENTRY_LEN;
-                let point = decode_point(&buf[start..start + TOUCHPOINT_ENTRY_LEN]);
-                points.push(point).ok();
-            }
+        if self.auto_clear {
+            self.clear_status(i2c).await?;
+        }
+        Ok(points.first().cloned())
+    }
 
-            points
+    // reads the status register into buf[0] and the touch points into the rest of buf, in one transfer
+    // when the touch points directly follow the status register (always the case with the standard map)
+    async fn read_frame(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<(), E> {
+        if self.registers.touchpoint_1 == self.registers.status + 1 {
+            self.read(i2c, self.registers.status, buf).await
         } else {
-            heapless::Vec::new()
-        };
+            let (status, points) = buf.split_at_mut(1);
+            self.read(i2c, self.registers.status, status).await?;
+            self.read(i2c, self.registers.touchpoint_1, points).await
+        }
+    }
 
-        // clear status register
-        self.write(i2c, GT911_TOUCHPOINT_STATUS_REG, 0).await?;
-        Ok(points)
+    // reads the status register and up to max_points touch points without clearing the status register
+    // with the torn frame check enabled the status register is read again afterwards and the whole read
+    // is retried if the controller updated the frame in the meantime
+    async fn read_points(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        max_points: usize,
+    ) -> Result<heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>, E> {
+        self.read_status_and_points(i2c, buf, max_points)
+            .await
+            .map(|(_, points)| points)
     }
 
-    async fn get_num_touch_points(&self, i2c: &mut I2C, buf: &mut [u8]) -> Result<usize, Error<E>> {
-        // read coords
-        assert!(!buf.is_empty());
-        self.read(i2c, GT911_TOUCHPOINT_STATUS_REG, &mut buf[..1])
+    // same as read_points but also returns the raw status register of the frame
+    async fn read_status_and_points(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        max_points: usize,
+    ) -> Result<(u8, heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>), E> {
+        let max_retries = self.torn_frame_check.then_some(TORN_FRAME_RETRIES);
+        self.read_status_and_points_checked(i2c, buf, max_points, max_retries)
+            .await
+            .map_err(torn_frame_as_not_ready)
+    }
+
+    // re-reads the status register after the touch points when max_retries is set and starts over
+    // if it changed, Err(Error::TornFrame) once max_retries retries have all been torn
+    async fn read_status_and_points_checked(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        max_points: usize,
+        max_retries: Option<usize>,
+    ) -> Result<(u8, heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>), E> {
+        let (status, len) = self
+            .read_status_and_entries(i2c, buf, max_points, max_retries)
             .await?;
+        let mut points = heapless::Vec::new();
+        decode_points(
+            &buf[1..1 + len],
+            self.reverse_slots,
+            self.offset,
+            &self.exclusion_zones,
+            &mut points,
+        )?;
+        Ok((status, points))
+    }
+
+    // same as read_status_and_points_checked but leaves the raw touch point entries in buf[1..]
+    // returns the status register and the number of bytes of buf[1..] holding entries
+    // the status register is polled on its own so that no data and release frames (ready with zero touch
+    // points) never touch the coordinate registers, stale bytes there can't be decoded as a phantom point.
+    // Only once it reports touch points are the status and the touch points read together in one transfer
+    // (with the standard register map, see read_frame)
+    async fn read_status_and_entries(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        max_points: usize,
+        max_retries: Option<usize>,
+    ) -> Result<(u8, usize), E> {
+        let mut retries = 0;
+        loop {
+            self.read(i2c, self.registers.status, &mut buf[..1]).await?;
+            let polled = buf[0];
+            let len = decode_num_touch_points(polled)?.min(max_points) * TOUCHPOINT_ENTRY_LEN;
+            if len == 0 {
+                return Ok((polled, 0));
+            }
 
-        let status = buf[0];
-        let ready = (status & 0x80) > 0;
-        let num_touch_points = (status & 0x0F) as usize;
+            if self.registers.touchpoint_1 == self.registers.status + 1 {
+                self.read(i2c, self.registers.status, &mut buf[..1 + len])
+                    .await?;
+            } else {
+                // the registers are not adjacent so the status can't come along, the poll stands in for it
+                self.read(i2c, self.registers.touchpoint_1, &mut buf[1..1 + len])
+                    .await?;
+                buf[0] = polled;
+            }
+            let status = buf[0];
+            // the frame changed between the poll and the combined read so len may not match its touch count
+            let torn = status != polled;
 
-        if ready {
-            Ok(num_touch_points)
-        } else {
-            Err(Error::NotReady)
+            if !torn {
+                if max_retries.is_none() {
+                    return Ok((status, len));
+                }
+                self.read(i2c, self.registers.status, &mut buf[..1]).await?;
+                if buf[0] == status {
+                    return Ok((status, len));
+                }
+            }
+
+            retries += 1;
+            if retries > max_retries.unwrap_or(TORN_FRAME_RETRIES) {
+                return Err(Error::TornFrame);
+            }
         }
     }
 
-    async fn write(&self, i2c: &mut I2C, register: u16, value: u8) -> Result<(), Error<E>> {
+    // reads the whole config block, lets f modify it and writes it back along with
+    // a recalculated checksum and the config fresh flag so that the controller applies it
+    async fn update_config(
+        &self,
+        i2c: &mut I2C,
+        buf: &mut [u8],
+        f: impl FnOnce(&mut [u8]),
+    ) -> Result<(), E> {
+        assert!(
+            buf.len() >= CONFIG_BUF_SIZE,
+            "Buffer too small, use CONFIG_BUF_SIZE"
+        );
+        let buf = &mut buf[..CONFIG_BUF_SIZE];
+        self.read(i2c, GT911_CONFIG_START_REG, &mut buf[2..2 + CONFIG_LEN])
+            .await?;
+        f(&mut buf[2..2 + CONFIG_LEN]);
+        prepare_config_write(buf);
+        i2c.write(self.i2c_addr, buf).await.map_err(Error::I2C)
+    }
+
+    async fn write(&self, i2c: &mut I2C, register: u16, value: u8) -> Result<(), E> {
         let register = register.to_be_bytes();
         let cmd = [register[0], register[1], value];
         i2c.write(self.i2c_addr, &cmd).await.map_err(Error::I2C)
     }
 
-    async fn read(&self, i2c: &mut I2C, register: u16, buf: &mut [u8]) -> Result<(), Error<E>> {
-        i2c.write_read(self.i2c_addr, &register.to_be_bytes(), buf)
-            .await
-            .map_err(Error::I2C)
+    async fn read(&self, i2c: &mut I2C, register: u16, buf: &mut [u8]) -> Result<(), E> {
+        let register = register.to_be_bytes();
+        match self.read_mode {
+            ReadMode::Combined => i2c.write_read(self.i2c_addr, &register, buf).await,
+            ReadMode::Split => match i2c.write(self.i2c_addr, &register).await {
+                Ok(()) => i2c.read(self.i2c_addr, buf).await,
+                Err(e) => Err(e),
+            },
+            ReadMode::SplitWithDummy => match i2c.write(self.i2c_addr, &register).await {
+                Ok(()) => {
+                    // adjacent reads are one read on the bus, the first byte is thrown away
+                    let mut dummy = [0u8; 1];
+                    i2c.transaction(
+                        self.i2c_addr,
+                        &mut [Operation::Read(&mut dummy), Operation::Read(buf)],
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
+            },
+        }
+        .map_err(Error::I2C)
+    }
+}
+
+// polls future and timeout together, None if timeout completes first
+async fn with_timeout<T>(
+    future: impl Future<Output = T>,
+    timeout: impl Future<Output = ()>,
+) -> Option<T> {
+    let mut future = pin!(future);
+    let mut timeout = pin!(timeout);
+    core::future::poll_fn(|cx| {
+        if let task::Poll::Ready(output) = future.as_mut().poll(cx) {
+            return task::Poll::Ready(Some(output));
+        }
+        timeout.as_mut().poll(cx).map(|_| None)
+    })
+    .await
+}
+
+// register of the entry for touch point slot (0-4) given the register of the first entry
+fn touchpoint_reg(touchpoint_1: u16, slot: u8) -> u16 {
+    assert!((slot as usize) < MAX_NUM_TOUCHPOINTS);
+    touchpoint_1 + slot as u16 * TOUCHPOINT_ENTRY_LEN as u16
+}
+
+// decodes consecutive touch point entries, last slot first if reverse is set
+// points inside any of the exclusion zones are dropped
+fn decode_points<E>(
+    buf: &[u8],
+    reverse: bool,
+    offset: (i16, i16),
+    exclusion_zones: &[ExclusionZone],
+    points: &mut heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>,
+) -> Result<(), E> {
+    for_each_point(buf, reverse, false, offset, exclusion_zones, |point| {
+        points.push(point).ok();
+    })?;
+    Ok(())
+}
+
+// decodes consecutive touch point entries straight from buf and calls f for every point without collecting them
+// slots are visited last first if reverse is set and in track_id order if sort is set
+// points inside any of the exclusion zones are skipped. Returns the number of points passed to f
+fn for_each_point<E>(
+    buf: &[u8],
+    reverse: bool,
+    sort: bool,
+    offset: (i16, i16),
+    exclusion_zones: &[ExclusionZone],
+    mut f: impl FnMut(Point),
+) -> Result<usize, E> {
+    // check every entry first so that a short read never hands half a frame to f
+    for entry in buf.chunks_exact(TOUCHPOINT_ENTRY_LEN) {
+        decode_entry::<E>(entry, offset)?;
+    }
+
+    let mut slots: [usize; MAX_NUM_TOUCHPOINTS] = core::array::from_fn(|slot| slot);
+    let slots = &mut slots[..(buf.len() / TOUCHPOINT_ENTRY_LEN).min(MAX_NUM_TOUCHPOINTS)];
+    if reverse {
+        slots.reverse();
+    }
+    if sort {
+        slots.sort_unstable_by_key(|slot| buf[slot * TOUCHPOINT_ENTRY_LEN]);
+    }
+
+    let mut count = 0;
+    for slot in slots.iter() {
+        let entry = &buf[slot * TOUCHPOINT_ENTRY_LEN..(slot + 1) * TOUCHPOINT_ENTRY_LEN];
+        let point = decode_entry(entry, offset)?;
+        if !excluded(exclusion_zones, &point) {
+            f(point);
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+// the torn frame check of the regular reads reports a frame that keeps changing as no data
+fn torn_frame_as_not_ready<E>(error: Error<E>) -> Error<E> {
+    match error {
+        Error::TornFrame => Error::NotReady,
+        error => error,
+    }
+}
+
+fn excluded(exclusion_zones: &[ExclusionZone], point: &Point) -> bool {
+    exclusion_zones.iter().any(|zone| zone.contains(point))
+}
+
+// Err(Error::ShortRead) for an entry that is all 0xFF which no real touch point can be
+// (track_id 255 and reserved byte 255), the data never made it off the bus
+// offset is added to x and y, clamped to the u16 range
+fn decode_entry<E>(buf: &[u8], offset: (i16, i16)) -> Result<Point, E> {
+    if buf[..TOUCHPOINT_ENTRY_LEN].iter().all(|byte| *byte == 0xFF) {
+        return Err(Error::ShortRead);
+    }
+    let mut point = decode_point(buf);
+    point.x = point.x.saturating_add_signed(offset.0);
+    point.y = point.y.saturating_add_signed(offset.1);
+    Ok(point)
+}
+
+// Err(Error::NotReady) unless the buffer status (ready) bit is set
+// the 4 bit count is clamped to the max number of touch points so that a corrupt status byte
+// (e.g. 0xFF from a noisy bus) can never cause a read past the touch point registers
+fn decode_num_touch_points<E>(status: u8) -> Result<usize, E> {
+    let ready = (status & 0x80) > 0;
+    let num_touch_points = ((status & 0x0F) as usize).min(MAX_NUM_TOUCHPOINTS);
+
+    if ready {
+        Ok(num_touch_points)
+    } else {
+        Err(Error::NotReady)
+    }
+}
+
+// None when no gesture is latched (code 0)
+fn decode_gesture(code: u8) -> Option<Gesture> {
+    match code {
+        0 => None,
+        0xCC => Some(Gesture::DoubleTap),
+        0xBA => Some(Gesture::SwipeUp),
+        0xAB => Some(Gesture::SwipeDown),
+        0xBB => Some(Gesture::SwipeLeft),
+        0xAA => Some(Gesture::SwipeRight),
+        code if code.is_ascii_lowercase() => Some(Gesture::Letter(code)),
+        code => Some(Gesture::Other(code)),
+    }
+}
+
+// little-endian value of a 1 or 2 byte config field
+fn decode_config_field(buf: &[u8]) -> u16 {
+    match buf {
+        [lo, hi] => u16::from_le_bytes([*lo, *hi]),
+        _ => buf[0] as u16,
     }
 }
 
+// writes value into the config block at the offset of field
+fn encode_config_field(field: ConfigField, value: u16, config: &mut [u8]) {
+    let offset = config_offset(field.register());
+    config[offset..offset + field.width()].copy_from_slice(&value.to_le_bytes()[..field.width()]);
+}
+
+fn decode_touch_status<E>(status: u8) -> Result<TouchStatus, E> {
+    Ok(TouchStatus {
+        num_touch_points: decode_num_touch_points(status)? as u8,
+        large_detect: status & 0x40 != 0,
+        have_key: status & 0x10 != 0,
+    })
+}
+
 fn decode_point(buf: &[u8]) -> Point {
     assert!(buf.len() >= TOUCHPOINT_ENTRY_LEN);
     Point {
@@ -350,3 +3322,206 @@ fn decode_point(buf: &[u8]) -> Point {
         // NOTE: the last byte is reserved
     }
 }
+
+// offset of a config register within the config block
+fn config_offset(register: u16) -> usize {
+    (register - GT911_CONFIG_START_REG) as usize
+}
+
+// the config checksum is the two's complement of the sum of all config bytes
+fn config_checksum(config: &[u8]) -> u8 {
+    let sum = config.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+    (!sum).wrapping_add(1)
+}
+
+// fills in the register address, checksum and fresh flag around the config block in buf
+fn prepare_config_write(buf: &mut [u8]) {
+    assert!(buf.len() >= CONFIG_BUF_SIZE);
+    let register = GT911_CONFIG_START_REG.to_be_bytes();
+    buf[0] = register[0];
+    buf[1] = register[1];
+    buf[2 + CONFIG_LEN] = config_checksum(&buf[2..2 + CONFIG_LEN]);
+    buf[2 + CONFIG_LEN + 1] = 1;
+}
+
+// integer square root rounded down (Newton's method)
+fn isqrt(value: u64) -> u64 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value / 2 + 1;
+    let mut y = (x + value / x) / 2;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+fn to_i16(value: u16) -> i16 {
+    i16::try_from(value).unwrap_or(i16::MAX)
+}
+
+fn decode_sensor_geometry(buf: &[u8]) -> SensorGeometry {
+    assert!(buf.len() >= 3);
+    SensorGeometry {
+        driver_group_a: buf[0] & 0x1F,
+        driver_group_b: buf[1] & 0x1F,
+        sensor_group_a: buf[2] & 0x0F,
+        sensor_group_b: buf[2] >> 4,
+    }
+}
+
+// writes the line counts into the config bytes at 0x8062-0x8064, keeping the other bits of the driver group bytes
+fn encode_sensor_geometry(geometry: &SensorGeometry, buf: &mut [u8]) {
+    assert!(geometry.driver_group_a <= 0x1F && geometry.driver_group_b <= 0x1F);
+    assert!(geometry.sensor_group_a <= 0x0F && geometry.sensor_group_b <= 0x0F);
+    buf[0] = (buf[0] & !0x1F) | geometry.driver_group_a;
+    buf[1] = (buf[1] & !0x1F) | geometry.driver_group_b;
+    buf[2] = (geometry.sensor_group_b << 4) | geometry.sensor_group_a;
+}
+
+fn decode_border_space(buf: &[u8]) -> BorderSpace {
+    assert!(buf.len() >= 2);
+    BorderSpace {
+        top: buf[0] >> 4,
+        bottom: buf[0] & 0x0F,
+        left: buf[1] >> 4,
+        right: buf[1] & 0x0F,
+    }
+}
+
+fn encode_border_space(border: &BorderSpace, buf: &mut [u8]) {
+    buf[0] = (border.top << 4) | border.bottom;
+    buf[1] = (border.left << 4) | border.right;
+}
+
+fn decode_stretch(buf: &[u8]) -> StretchConfig {
+    assert!(buf.len() >= 4);
+    StretchConfig {
+        r0: buf[0],
+        r1: buf[1],
+        r2: buf[2],
+        rm: buf[3],
+    }
+}
+
+fn encode_stretch(stretch: &StretchConfig, buf: &mut [u8]) {
+    buf[..4].copy_from_slice(&[stretch.r0, stretch.r1, stretch.r2, stretch.rm]);
+}
+
+fn decode_frequency_hopping(buf: &[u8]) -> FrequencyHopping {
+    assert!(buf.len() >= 4);
+    FrequencyHopping {
+        start: buf[0],
+        end: buf[1],
+        noise_detect_times: buf[2],
+        flags: buf[3],
+    }
+}
+
+fn encode_frequency_hopping(hopping: &FrequencyHopping, buf: &mut [u8]) {
+    buf[..4].copy_from_slice(&[
+        hopping.start,
+        hopping.end,
+        hopping.noise_detect_times,
+        hopping.flags,
+    ]);
+}
+
+fn decode_resolution(buf: &[u8]) -> (u16, u16) {
+    assert!(buf.len() >= 4);
+    (
+        u16::from_le_bytes([buf[0], buf[1]]),
+        u16::from_le_bytes([buf[2], buf[3]]),
+    )
+}
+
+// scales value to Q16.16 fixed point relative to max, clamped to 1.0
+fn normalize(value: u16, max: u16) -> u32 {
+    if max == 0 {
+        return 0;
+    }
+    (((value as u32) << 16) / max as u32).min(1 << 16)
+}
+
+fn decode_touch_keys(buf: &[u8]) -> TouchKeyConfig {
+    assert!(buf.len() >= TOUCH_KEY_CONFIG_LEN);
+    TouchKeyConfig {
+        positions: [buf[0], buf[1], buf[2], buf[3]],
+        area: buf[4],
+        touch_level: buf[5],
+        leave_level: buf[6],
+        sensitivity: [buf[7] >> 4, buf[7] & 0x0F, buf[8] >> 4, buf[8] & 0x0F],
+    }
+}
+
+fn encode_touch_keys(keys: &TouchKeyConfig, buf: &mut [u8]) {
+    assert!(buf.len() >= TOUCH_KEY_CONFIG_LEN);
+    assert!(keys
+        .sensitivity
+        .iter()
+        .all(|sensitivity| *sensitivity <= 0x0F));
+    buf[..4].copy_from_slice(&keys.positions);
+    buf[4] = keys.area;
+    buf[5] = keys.touch_level;
+    buf[6] = keys.leave_level;
+    buf[7] = (keys.sensitivity[0] << 4) | keys.sensitivity[1];
+    buf[8] = (keys.sensitivity[2] << 4) | keys.sensitivity[3];
+}
+
+// the product id without the trailing nul characters, shared by ProbeInfo and VersionInfo
+fn product_id_str(product_id: &[u8; 4]) -> Option<&str> {
+    str::from_utf8(product_id)
+        .ok()
+        .map(|product_id| product_id.trim_end_matches('\0'))
+}
+
+fn decode_probe_info(buf: &[u8], config_version: u8) -> ProbeInfo {
+    assert!(buf.len() >= PRODUCT_INFO_LEN);
+    ProbeInfo {
+        product_id: [buf[0], buf[1], buf[2], buf[3]],
+        firmware_version: u16::from_le_bytes([buf[4], buf[5]]),
+        sensor_id: buf[10],
+        config_version,
+        width: u16::from_le_bytes([buf[6], buf[7]]),
+        height: u16::from_le_bytes([buf[8], buf[9]]),
+    }
+}
+
+// product_info is the product info block (0x8140-0x814A) and config the config block followed by its checksum
+fn decode_self_test(product_info: &[u8], config: &[u8]) -> SelfTestReport {
+    assert!(config.len() > CONFIG_LEN);
+    let info = decode_probe_info(product_info, config[0]);
+    let offset = config_offset(GT911_X_OUTPUT_MAX_REG);
+    let width = u16::from_le_bytes([config[offset], config[offset + 1]]);
+    let height = u16::from_le_bytes([config[offset + 2], config[offset + 3]]);
+    SelfTestReport {
+        version: decode_version_info(product_info, config[0], config[CONFIG_LEN]),
+        product_id_ok: info.product_id_str() == Some("911"),
+        firmware_ok: info.firmware_version != 0 && info.firmware_version != 0xFFFF,
+        config_checksum_ok: config_checksum(&config[..CONFIG_LEN]) == config[CONFIG_LEN],
+        resolution_ok: info.width != 0
+            && info.height != 0
+            && (info.width, info.height) == (width, height),
+    }
+}
+
+fn decode_version_info(buf: &[u8], config_version: u8, config_checksum: u8) -> VersionInfo {
+    let info = decode_probe_info(buf, config_version);
+    VersionInfo {
+        product_id: info.product_id,
+        firmware_version: info.firmware_version,
+        sensor_id: info.sensor_id,
+        config_version,
+        config_checksum,
+    }
+}
+
+// scales value from 0..max to 0..range as Q16.16 fixed point
+fn scale_q16(value: u16, max: u16, range: u16) -> u32 {
+    if max == 0 {
+        return 0;
+    }
+    (((value as u64 * range as u64) << 16) / max as u64).min(u32::MAX as u64) as u32
+}
@@ -0,0 +1,136 @@
+// A register level GT911 simulator for testing touch handling code without hardware (enabled with the mock feature)
+
+use crate::{Point, ADDR_5D, GT911_COMMAND_REG, GT911_PRODUCT_ID_REG, MAX_NUM_TOUCHPOINTS};
+use crate::{GT911_TOUCHPOINT_1_REG, GT911_TOUCHPOINT_STATUS_REG, TOUCHPOINT_ENTRY_LEN};
+use embedded_hal::i2c::{ErrorKind, ErrorType, NoAcknowledgeSource, Operation};
+
+// simulated registers: command register through the touch point registers (0x8040-0x817F)
+const FIRST_REG: u16 = GT911_COMMAND_REG;
+const REGS_LEN: usize = 0x140;
+
+/// Simulated GT911 implementing the blocking and async embedded-hal I2c traits
+/// Registers 0x8040-0x817F (command, config, product info, status and touch points) behave like plain memory
+/// with an auto-incrementing register pointer, reads outside that range return 0 and writes are ignored.
+/// The product id reads "911\0" so init succeeds. Load frames with set_touches / set_release and check
+/// whether the driver consumed them (cleared the status register) with is_ready
+#[derive(Debug, Clone)]
+pub struct Gt911Mock {
+    addr: u8,
+    regs: [u8; REGS_LEN],
+    pointer: u16,
+}
+
+impl Default for Gt911Mock {
+    fn default() -> Self {
+        Self::new(ADDR_5D)
+    }
+}
+
+impl Gt911Mock {
+    /// Creates a simulated controller answering at i2c_addr, transactions to any other address are not acknowledged
+    pub fn new(i2c_addr: u8) -> Self {
+        let mut mock = Self {
+            addr: i2c_addr,
+            regs: [0u8; REGS_LEN],
+            pointer: 0,
+        };
+        for (n, byte) in b"911\0".iter().enumerate() {
+            mock.set_register(GT911_PRODUCT_ID_REG + n as u16, *byte);
+        }
+        mock
+    }
+
+    /// Loads a touch frame with up to 5 points (extra points are ignored) and marks it ready
+    pub fn set_touches(&mut self, points: &[Point]) {
+        let points = &points[..points.len().min(MAX_NUM_TOUCHPOINTS)];
+        for (slot, point) in points.iter().enumerate() {
+            let x = point.x.to_le_bytes();
+            let y = point.y.to_le_bytes();
+            let area = point.area.to_le_bytes();
+            let entry = [point.track_id, x[0], x[1], y[0], y[1], area[0], area[1], 0];
+            let reg = GT911_TOUCHPOINT_1_REG + (slot * TOUCHPOINT_ENTRY_LEN) as u16;
+            for (n, byte) in entry.iter().enumerate() {
+                self.set_register(reg + n as u16, *byte);
+            }
+        }
+        self.set_register(GT911_TOUCHPOINT_STATUS_REG, 0x80 | points.len() as u8);
+    }
+
+    /// Loads a release frame (ready with no touch points)
+    pub fn set_release(&mut self) {
+        self.set_touches(&[]);
+    }
+
+    /// Whether a frame is loaded that the driver has not consumed (cleared the status register) yet
+    pub fn is_ready(&self) -> bool {
+        self.register(GT911_TOUCHPOINT_STATUS_REG) & 0x80 != 0
+    }
+
+    /// Reads a simulated register, 0 outside the simulated range
+    pub fn register(&self, register: u16) -> u8 {
+        Self::index(register).map_or(0, |index| self.regs[index])
+    }
+
+    /// Writes a simulated register, ignored outside the simulated range
+    pub fn set_register(&mut self, register: u16, value: u8) {
+        if let Some(index) = Self::index(register) {
+            self.regs[index] = value;
+        }
+    }
+
+    fn index(register: u16) -> Option<usize> {
+        let index = register.checked_sub(FIRST_REG)? as usize;
+        (index < REGS_LEN).then_some(index)
+    }
+
+    fn execute(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), ErrorKind> {
+        if address != self.addr {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => {
+                    if let [hi, lo, data @ ..] = bytes {
+                        self.pointer = u16::from_be_bytes([*hi, *lo]);
+                        for byte in data.iter() {
+                            self.set_register(self.pointer, *byte);
+                            self.pointer = self.pointer.wrapping_add(1);
+                        }
+                    }
+                }
+                Operation::Read(buf) => {
+                    for byte in buf.iter_mut() {
+                        *byte = self.register(self.pointer);
+                        self.pointer = self.pointer.wrapping_add(1);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ErrorType for Gt911Mock {
+    type Error = ErrorKind;
+}
+
+impl embedded_hal::i2c::I2c for Gt911Mock {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.execute(address, operations)
+    }
+}
+
+impl embedded_hal_async::i2c::I2c for Gt911Mock {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.execute(address, operations)
+    }
+}
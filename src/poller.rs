@@ -0,0 +1,161 @@
+// Drives a blocking multi-touch read as a state machine, one I2C transaction per call
+
+use core::sync::atomic::Ordering;
+
+use crate::{
+    decode_num_touch_points, decode_points, Error, Gt911Blocking, Point, Result,
+    MAX_NUM_TOUCHPOINTS, TORN_FRAME_RETRIES, TOUCHPOINT_ENTRY_LEN,
+};
+
+/// Result of a TouchPoller::poll call
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Poll<T> {
+    /// A transaction was done but the read is not complete, call poll again
+    Pending,
+    /// The read is complete, the next poll call starts a new read
+    Ready(T),
+}
+
+#[derive(Debug, Clone)]
+enum State {
+    ReadStatus,
+    ReadPoints {
+        status: u8,
+        num_touch_points: usize,
+    },
+    // torn frame check: the status register is read again before clearing it
+    RecheckStatus {
+        status: u8,
+        points: heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>,
+    },
+    ClearStatus {
+        points: heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>,
+    },
+}
+
+/// A non-blocking equivalent of Gt911Blocking::get_multi_touch for cooperative schedulers
+/// Every poll call does at most one I2C transaction (status, touch points, clear) so touch reads
+/// can be interleaved with other work. The options of the driver (sorting, slot order, torn frame check, auto clear) are honored
+pub struct TouchPoller<'a, I2C> {
+    touch: &'a Gt911Blocking<I2C>,
+    state: State,
+    retries: usize,
+}
+
+impl<'a, I2C, E> TouchPoller<'a, I2C>
+where
+    I2C: embedded_hal::i2c::I2c<Error = E>,
+{
+    /// Creates a poller that reads touch points using touch (e.g. its i2c address and options)
+    pub fn new(touch: &'a Gt911Blocking<I2C>) -> Self {
+        Self {
+            touch,
+            state: State::ReadStatus,
+            retries: 0,
+        }
+    }
+
+    /// Does the next transaction of the read
+    /// Returns Poll::Ready with the same result get_multi_touch would have returned once the read is complete
+    /// and Poll::Pending otherwise. Any error ends the read and the next call starts over
+    pub fn poll(
+        &mut self,
+        i2c: &mut I2C,
+    ) -> Poll<Result<heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>, E>> {
+        match self.step(i2c) {
+            Ok(Some(points)) => {
+                self.reset();
+                Poll::Ready(Ok(points))
+            }
+            Ok(None) => Poll::Pending,
+            Err(e) => {
+                self.reset();
+                Poll::Ready(Err(e))
+            }
+        }
+    }
+
+    /// Abandons a read in progress, the next poll call starts a new read
+    /// Note that the frame is left unconsumed on the controller so the next read returns it again
+    pub fn reset(&mut self) {
+        self.state = State::ReadStatus;
+        self.retries = 0;
+    }
+
+    // does one transaction and moves to the next state, Some(points) once the status register is cleared
+    fn step(
+        &mut self,
+        i2c: &mut I2C,
+    ) -> Result<Option<heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>>, E> {
+        let touch = self.touch;
+        match core::mem::replace(&mut self.state, State::ReadStatus) {
+            State::ReadStatus => {
+                touch.last_cleared.store(false, Ordering::Relaxed);
+                let mut read = [0u8; 1];
+                touch.read(i2c, touch.registers.status, &mut read)?;
+                let status = read[0];
+                let num_touch_points = decode_num_touch_points(status)?;
+
+                // release frame: never read the coordinate registers (see read_points)
+                self.state = if num_touch_points > 0 {
+                    State::ReadPoints {
+                        status,
+                        num_touch_points,
+                    }
+                } else {
+                    self.after_points(status, heapless::Vec::new())
+                };
+            }
+            State::ReadPoints {
+                status,
+                num_touch_points,
+            } => {
+                let mut read = [0u8; TOUCHPOINT_ENTRY_LEN * MAX_NUM_TOUCHPOINTS];
+                let read = &mut read[..TOUCHPOINT_ENTRY_LEN * num_touch_points];
+                touch.read(i2c, touch.registers.touchpoint_1, read)?;
+                let mut points = heapless::Vec::new();
+                decode_points(
+                    read,
+                    touch.reverse_slots,
+                    touch.offset,
+                    &touch.exclusion_zones,
+                    &mut points,
+                )?;
+                self.state = self.after_points(status, points);
+            }
+            State::RecheckStatus { status, points } => {
+                let mut read = [0u8; 1];
+                touch.read(i2c, touch.registers.status, &mut read)?;
+                if read[0] == status {
+                    self.state = State::ClearStatus { points };
+                } else {
+                    // the controller updated the frame mid-read, start over
+                    self.retries += 1;
+                    if self.retries > TORN_FRAME_RETRIES {
+                        return Err(Error::NotReady);
+                    }
+                }
+            }
+            State::ClearStatus { mut points } => {
+                if touch.auto_clear {
+                    touch.clear_status(i2c)?;
+                }
+                if touch.sort_points {
+                    points.sort_unstable_by_key(|point| point.track_id);
+                }
+                return Ok(Some(points));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn after_points(&self, status: u8, points: heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>) -> State {
+        if self.touch.torn_frame_check {
+            State::RecheckStatus { status, points }
+        } else {
+            State::ClearStatus { points }
+        }
+    }
+}
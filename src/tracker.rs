@@ -0,0 +1,595 @@
+// Keeps per-finger state across frames on top of the stateless driver
+
+use crate::{Point, MAX_NUM_TOUCHPOINTS};
+
+/// Default number of positions kept per finger by TouchTracker
+pub const DEFAULT_HISTORY_LEN: usize = 8;
+
+#[derive(Debug, Clone)]
+struct Track<const N: usize> {
+    point: Point,
+    // position in the frame before the current one, None on the first frame of a touch
+    previous: Option<(u16, u16)>,
+    peak_area: u16,
+    // last N positions, oldest first
+    history: heapless::Vec<(u16, u16), N>,
+    // number of consecutive frames the position has not changed
+    unchanged_frames: u32,
+    // frame counter value when the finger was pressed
+    pressed_frame: u32,
+}
+
+/// What happened to a finger in the latest frame, see TouchTracker::events
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TouchEvent {
+    /// A finger touched down
+    Pressed(Point),
+    /// A finger that was already down was reported again (usually at a new position)
+    Moved(Point),
+    /// A finger was lifted, the point is where it was last seen
+    Released(Point),
+    /// A finger that has not moved for the stuck threshold number of frames, likely a phantom touch
+    /// from a faulty panel (see TouchTracker::with_stuck_threshold). Reported instead of Moved
+    Stuck(Point),
+}
+
+impl TouchEvent {
+    /// The point the event applies to
+    pub fn point(&self) -> &Point {
+        match self {
+            TouchEvent::Pressed(point)
+            | TouchEvent::Moved(point)
+            | TouchEvent::Released(point)
+            | TouchEvent::Stuck(point) => point,
+        }
+    }
+}
+
+/// The fingers down in the latest frame and the fingers lifted in it, see TouchTracker::frame
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackedFrame {
+    /// Points of the fingers currently down (pressed or moved)
+    pub active: heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>,
+    /// track_ids present in the previous frame but absent from the latest one
+    pub released: heapless::Vec<u8, MAX_NUM_TOUCHPOINTS>,
+}
+
+/// Coordinate smoothing applied by TouchTracker::smoothed over the last n positions of a finger
+/// n is capped at the history length N of the tracker
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Smoothing {
+    /// No smoothing, the latest position is used as is
+    None,
+    /// Moving average of the last n positions, reduces jitter but a single bad sample drags the output
+    Average(usize),
+    /// Moving median of the last n positions (3 or 5 are good choices), rejects the occasional glitchy
+    /// coordinate and keeps edges sharp during fast motion
+    Median(usize),
+}
+
+/// How TouchTracker::primary_touch picks the finger that drives a single pointer (e.g. a cursor)
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrimaryPolicy {
+    /// The finger with the lowest track_id
+    LowestTrackId,
+    /// The finger that has been down the longest, ties go to the lowest track_id
+    FirstPressed,
+    /// The finger with the largest area, ties go to the lowest track_id
+    LargestArea,
+}
+
+/// Tracks touch points across frames by track_id
+/// Feed it every frame returned by get_multi_touch, a track_id missing from a frame is considered released
+/// N is the number of (x, y) positions kept per finger, see history
+/// Synthetic in-between points for smoother drawing come from extrapolate (formerly requested as interpolate,
+/// renamed because it predicts ahead of the last frame rather than filling in between two frames)
+#[derive(Debug)]
+pub struct TouchTracker<const N: usize = DEFAULT_HISTORY_LEN> {
+    tracks: heapless::Vec<Track<N>, MAX_NUM_TOUCHPOINTS>,
+    // last known points of fingers lifted in the latest frame
+    released: heapless::Vec<Point, MAX_NUM_TOUCHPOINTS>,
+    smoothing: Smoothing,
+    // 0 disables stuck touch detection
+    stuck_threshold: u32,
+    primary_policy: PrimaryPolicy,
+    // number of updates so far (wrapping)
+    frame: u32,
+    // peak number of simultaneous touches, see max_touches_observed
+    max_touches: u8,
+    // max frames between the two releases of a double tap, 0 disables double tap detection
+    double_tap_window: u32,
+    double_tap_distance: u16,
+    // release point and frame of the last tap, the candidate first half of a double tap
+    last_tap: Option<(Point, u32)>,
+    // detected double tap not yet taken, see take_double_tap
+    double_tap: Option<Point>,
+}
+
+impl<const N: usize> Default for TouchTracker<N> {
+    fn default() -> Self {
+        Self {
+            tracks: heapless::Vec::new(),
+            released: heapless::Vec::new(),
+            smoothing: Smoothing::None,
+            stuck_threshold: 0,
+            primary_policy: PrimaryPolicy::LowestTrackId,
+            frame: 0,
+            max_touches: 0,
+            double_tap_window: 0,
+            double_tap_distance: 0,
+            last_tap: None,
+            double_tap: None,
+        }
+    }
+}
+
+impl TouchTracker {
+    /// Creates a tracker with no fingers pressed that keeps DEFAULT_HISTORY_LEN positions per finger
+    /// Use TouchTracker::<N>::default() for a different history length
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<const N: usize> TouchTracker<N> {
+    /// Selects the smoothing used by smoothed (Smoothing::None by default)
+    pub fn with_smoothing(mut self, smoothing: Smoothing) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Flags a finger as stuck once its position has not changed for frames consecutive frames (0, the default, disables it)
+    /// A failing panel can report a phantom touch at a fixed position forever, see is_stuck and TouchEvent::Stuck
+    /// Pick a threshold well above the longest a real finger holds perfectly still (e.g. several seconds of frames)
+    pub fn with_stuck_threshold(mut self, frames: u32) -> Self {
+        self.stuck_threshold = frames;
+        self
+    }
+
+    /// Selects how primary_touch picks a finger (PrimaryPolicy::LowestTrackId by default)
+    pub fn with_primary_policy(mut self, primary_policy: PrimaryPolicy) -> Self {
+        self.primary_policy = primary_policy;
+        self
+    }
+
+    /// Enables double tap detection, see take_double_tap (disabled by default)
+    /// A tap is a press released within window frames, a double tap is a second tap released at most
+    /// window frames after the first and within distance of it on both axes (in panel coordinates)
+    /// Counted in update calls so divide the time by the polling period (e.g. 300ms at 10ms polling is 30 frames)
+    pub fn with_double_tap(mut self, window: u32, distance: u16) -> Self {
+        self.double_tap_window = window;
+        self.double_tap_distance = distance;
+        self
+    }
+
+    /// Updates the tracker with the touch points of a new frame (an empty slice for a release frame)
+    pub fn update(&mut self, points: &[Point]) {
+        self.frame = self.frame.wrapping_add(1);
+        self.max_touches = self.max_touches.max(points.len() as u8);
+        let mut tracks = heapless::Vec::new();
+        for point in points {
+            let mut track = match self.track(point.track_id) {
+                Some(track) => Track {
+                    point: point.clone(),
+                    previous: Some((track.point.x, track.point.y)),
+                    peak_area: track.peak_area.max(point.area),
+                    history: track.history.clone(),
+                    unchanged_frames: if (point.x, point.y) == (track.point.x, track.point.y) {
+                        track.unchanged_frames.saturating_add(1)
+                    } else {
+                        0
+                    },
+                    pressed_frame: track.pressed_frame,
+                },
+                None => Track {
+                    point: point.clone(),
+                    previous: None,
+                    peak_area: point.area,
+                    history: heapless::Vec::new(),
+                    unchanged_frames: 0,
+                    pressed_frame: self.frame,
+                },
+            };
+
+            if N > 0 {
+                if track.history.is_full() {
+                    track.history.remove(0);
+                }
+                track.history.push((point.x, point.y)).ok();
+            }
+
+            tracks.push(track).ok();
+        }
+
+        self.released.clear();
+        let previous = core::mem::replace(&mut self.tracks, tracks);
+        for track in previous {
+            if !points
+                .iter()
+                .any(|point| point.track_id == track.point.track_id)
+            {
+                self.released.push(track.point.clone()).ok();
+                self.detect_double_tap(track.point, track.pressed_frame);
+            }
+        }
+    }
+
+    /// The press, move and release events of the latest frame (releases first)
+    /// This is the shape of input most GUI frameworks expect (e.g. pointer pressed/moved/released)
+    pub fn events(&self) -> impl Iterator<Item = TouchEvent> + '_ {
+        let released = self.released.iter().cloned().map(TouchEvent::Released);
+        let active = self.tracks.iter().map(|track| match track.previous {
+            None => TouchEvent::Pressed(track.point.clone()),
+            Some(_) if self.stuck(track) => TouchEvent::Stuck(track.point.clone()),
+            Some(_) => TouchEvent::Moved(track.point.clone()),
+        });
+        released.chain(active)
+    }
+
+    /// The active points and the track_ids of the fingers released in the latest frame
+    /// Use this when the identity of a lifted finger matters (e.g. which finger dropped a dragged item)
+    pub fn frame(&self) -> TrackedFrame {
+        TrackedFrame {
+            active: self
+                .tracks
+                .iter()
+                .map(|track| track.point.clone())
+                .collect(),
+            released: self.released.iter().map(|point| point.track_id).collect(),
+        }
+    }
+
+    /// Whether any finger was pressed in the latest frame (a track_id present now that was not in the previous frame)
+    /// The rising edge for button-like interactions where it does not matter which finger or where
+    pub fn pressed_this_frame(&self) -> bool {
+        self.tracks.iter().any(|track| track.previous.is_none())
+    }
+
+    /// Whether a finger has been at the same position for at least the stuck threshold number of frames
+    /// Always false when stuck touch detection is disabled or track_id is not currently pressed
+    pub fn is_stuck(&self, track_id: u8) -> bool {
+        self.track(track_id).is_some_and(|track| self.stuck(track))
+    }
+
+    /// The finger that drives a single pointer as selected by with_primary_policy or None if no finger is down
+    /// Unlike get_touch, which returns whatever the controller put in the first slot, this is predictable
+    /// when several fingers are down
+    pub fn primary_touch(&self) -> Option<Point> {
+        let tracks = self.tracks.iter();
+        let track = match self.primary_policy {
+            PrimaryPolicy::LowestTrackId => tracks.min_by_key(|track| track.point.track_id),
+            PrimaryPolicy::FirstPressed => tracks.min_by_key(|track| {
+                (
+                    core::cmp::Reverse(self.frame.wrapping_sub(track.pressed_frame)),
+                    track.point.track_id,
+                )
+            }),
+            PrimaryPolicy::LargestArea => tracks
+                .min_by_key(|track| (core::cmp::Reverse(track.point.area), track.point.track_id)),
+        };
+        track.map(|track| track.point.clone())
+    }
+
+    /// The number of frames a finger has been continuously down, 0 on the frame it was pressed
+    /// Counted in update calls so multiply by the polling period to get a time (e.g. for long press detection)
+    /// Resets on every new press. Returns None if track_id is not currently pressed
+    pub fn press_duration(&self, track_id: u8) -> Option<u32> {
+        self.track(track_id)
+            .map(|track| self.frame.wrapping_sub(track.pressed_frame))
+    }
+
+    /// The largest number of simultaneous touches seen since the tracker was created or reset_max_touches_observed
+    /// Useful to adapt the UI to the panel (e.g. only enable two finger gestures once two fingers have been seen)
+    pub fn max_touches_observed(&self) -> u8 {
+        self.max_touches
+    }
+
+    /// Restarts max_touches_observed from 0
+    pub fn reset_max_touches_observed(&mut self) {
+        self.max_touches = 0;
+    }
+
+    /// The largest area reached by a finger since it was pressed or None if track_id is not currently pressed
+    /// Useful for "press and hold" interactions where the peak pressure matters
+    pub fn peak_area(&self, track_id: u8) -> Option<u16> {
+        self.track(track_id).map(|track| track.peak_area)
+    }
+
+    /// The last N (x, y) positions of a finger, oldest first and including the current position
+    /// The history starts fresh on every press. Returns None if track_id is not currently pressed
+    pub fn history(&self, track_id: u8) -> Option<&[(u16, u16)]> {
+        self.track(track_id).map(|track| track.history.as_slice())
+    }
+
+    /// The latest point of a finger with x and y smoothed over its history as selected by with_smoothing
+    /// Returns None if track_id is not currently pressed
+    pub fn smoothed(&self, track_id: u8) -> Option<Point> {
+        let track = self.track(track_id)?;
+        let mut point = track.point.clone();
+        let (n, median) = match self.smoothing {
+            Smoothing::None => return Some(point),
+            Smoothing::Average(n) => (n, false),
+            Smoothing::Median(n) => (n, true),
+        };
+
+        let history = &track.history[track.history.len().saturating_sub(n)..];
+        if !history.is_empty() {
+            let filter = |axis: fn(&(u16, u16)) -> u16| {
+                let mut values: heapless::Vec<u16, N> = history.iter().map(axis).collect();
+                if median {
+                    values.sort_unstable();
+                    values[values.len() / 2]
+                } else {
+                    let sum: u32 = values.iter().map(|value| *value as u32).sum();
+                    (sum / values.len() as u32) as u16
+                }
+            };
+            point.x = filter(|(x, _)| *x);
+            point.y = filter(|(_, y)| *y);
+        }
+        Some(point)
+    }
+
+    /// Returns a SYNTHETIC point predicting where a finger is between hardware samples for smoother drawing
+    /// This extrapolates, it does not interpolate: the position is projected forward along the movement
+    /// between the last two frames, assuming constant velocity. elapsed is the time since the last frame and
+    /// interval the time between the last two frames, both in the same caller defined units (e.g. timer ticks).
+    /// elapsed is capped at interval so the prediction never runs more than one frame ahead.
+    /// Returns None if track_id is not currently pressed
+    pub fn extrapolate(&self, track_id: u8, elapsed: u32, interval: u32) -> Option<Point> {
+        let track = self.track(track_id)?;
+        let mut point = track.point.clone();
+        if let (Some((x, y)), true) = (track.previous, interval > 0) {
+            let elapsed = elapsed.min(interval) as i64;
+            let project = |from: u16, to: u16| {
+                let delta = to as i64 - from as i64;
+                (to as i64 + delta * elapsed / interval as i64).clamp(0, u16::MAX as i64) as u16
+            };
+            point.x = project(x, point.x);
+            point.y = project(y, point.y);
+        }
+        Some(point)
+    }
+
+    /// The velocity of a finger between the last two frames in pixels per tick as Q16.16 fixed point (x, y)
+    /// dt is the time between the last two frames in caller defined ticks, so the unit is whatever the ticks are
+    /// (e.g. pass milliseconds for pixels per millisecond). Divide by 65536 (or shift right 16) for whole pixels.
+    /// Saturates at i32::MIN / i32::MAX and is (0, 0) on the frame the finger was pressed.
+    /// Returns None if track_id is not currently pressed or dt is 0
+    pub fn velocity_fixed(&self, track_id: u8, dt: u32) -> Option<(i32, i32)> {
+        let track = self.track(track_id)?;
+        if dt == 0 {
+            return None;
+        }
+        let Some((x, y)) = track.previous else {
+            return Some((0, 0));
+        };
+        let velocity = |from: u16, to: u16| {
+            let delta = (to as i64 - from as i64) << 16;
+            (delta / dt as i64).clamp(i32::MIN as i64, i32::MAX as i64) as i32
+        };
+        Some((velocity(x, track.point.x), velocity(y, track.point.y)))
+    }
+
+    /// Returns the point of the second tap of the latest double tap and clears it, None if there was none
+    /// Detection has to be enabled with with_double_tap. The double tap is kept until taken so poll this
+    /// whenever it suits the application, not necessarily after every update
+    pub fn take_double_tap(&mut self) -> Option<Point> {
+        self.double_tap.take()
+    }
+
+    // checks whether the release of a finger completes a double tap
+    fn detect_double_tap(&mut self, point: Point, pressed_frame: u32) {
+        let window = self.double_tap_window;
+        if window == 0 || self.frame.wrapping_sub(pressed_frame) > window {
+            // disabled or held too long to be a tap
+            self.last_tap = None;
+            return;
+        }
+
+        match self.last_tap.take() {
+            Some((first, frame))
+                if self.frame.wrapping_sub(frame) <= window
+                    && first.x.abs_diff(point.x) <= self.double_tap_distance
+                    && first.y.abs_diff(point.y) <= self.double_tap_distance =>
+            {
+                self.double_tap = Some(point);
+            }
+            _ => self.last_tap = Some((point, self.frame)),
+        }
+    }
+
+    fn stuck(&self, track: &Track<N>) -> bool {
+        self.stuck_threshold > 0 && track.unchanged_frames >= self.stuck_threshold
+    }
+
+    fn track(&self, track_id: u8) -> Option<&Track<N>> {
+        self.tracks
+            .iter()
+            .find(|track| track.point.track_id == track_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(track_id: u8, x: u16, y: u16) -> Point {
+        Point {
+            track_id,
+            x,
+            y,
+            area: 10,
+        }
+    }
+
+    #[test]
+    fn smoothing_average_and_median() {
+        let mut average = TouchTracker::new().with_smoothing(Smoothing::Average(3));
+        let mut median = TouchTracker::new().with_smoothing(Smoothing::Median(3));
+        // a single glitchy sample in the middle
+        for x in [10, 100, 13] {
+            average.update(&[point(0, x, 20)]);
+            median.update(&[point(0, x, 20)]);
+        }
+
+        assert_eq!(average.smoothed(0).map(|p| (p.x, p.y)), Some((41, 20)));
+        assert_eq!(median.smoothed(0).map(|p| (p.x, p.y)), Some((13, 20)));
+        assert_eq!(median.smoothed(1), None);
+    }
+
+    #[test]
+    fn stuck_after_threshold_frames_without_moving() {
+        let mut tracker = TouchTracker::new().with_stuck_threshold(3);
+        for _ in 0..3 {
+            tracker.update(&[point(0, 10, 20)]);
+        }
+        assert!(!tracker.is_stuck(0));
+
+        tracker.update(&[point(0, 10, 20)]);
+        assert!(tracker.is_stuck(0));
+        assert_eq!(
+            tracker.events().next(),
+            Some(TouchEvent::Stuck(point(0, 10, 20)))
+        );
+
+        // moving clears it
+        tracker.update(&[point(0, 11, 20)]);
+        assert!(!tracker.is_stuck(0));
+    }
+
+    #[test]
+    fn primary_policy_selection() {
+        let large = Point {
+            area: 50,
+            ..point(2, 30, 30)
+        };
+        let primary = |policy| {
+            let mut tracker = TouchTracker::new().with_primary_policy(policy);
+            tracker.update(&[point(3, 10, 10)]);
+            tracker.update(&[point(3, 10, 10), point(1, 20, 20), large.clone()]);
+            tracker.primary_touch().map(|point| point.track_id)
+        };
+
+        assert_eq!(primary(PrimaryPolicy::LowestTrackId), Some(1));
+        assert_eq!(primary(PrimaryPolicy::FirstPressed), Some(3));
+        assert_eq!(primary(PrimaryPolicy::LargestArea), Some(2));
+        assert_eq!(TouchTracker::new().primary_touch(), None);
+    }
+
+    #[test]
+    fn double_tap_window_and_distance() {
+        let tap = |tracker: &mut TouchTracker, x| {
+            tracker.update(&[point(0, x, 20)]);
+            tracker.update(&[]);
+        };
+
+        let mut tracker = TouchTracker::new().with_double_tap(4, 5);
+        tap(&mut tracker, 100);
+        assert_eq!(tracker.take_double_tap(), None);
+        tap(&mut tracker, 104);
+        assert_eq!(tracker.take_double_tap(), Some(point(0, 104, 20)));
+        assert_eq!(tracker.take_double_tap(), None);
+
+        // second tap too far away
+        tap(&mut tracker, 100);
+        tap(&mut tracker, 106);
+        assert_eq!(tracker.take_double_tap(), None);
+
+        // second tap released after the window
+        let mut tracker = TouchTracker::new().with_double_tap(4, 5);
+        tap(&mut tracker, 100);
+        for _ in 0..3 {
+            tracker.update(&[]);
+        }
+        tap(&mut tracker, 100);
+        assert_eq!(tracker.take_double_tap(), None);
+
+        // disabled by default
+        let mut tracker = TouchTracker::new();
+        tap(&mut tracker, 100);
+        tap(&mut tracker, 100);
+        assert_eq!(tracker.take_double_tap(), None);
+    }
+
+    #[test]
+    fn velocity_fixed_point() {
+        let mut tracker = TouchTracker::new();
+        tracker.update(&[point(0, 10, 20)]);
+        assert_eq!(tracker.velocity_fixed(0, 4), Some((0, 0)));
+
+        tracker.update(&[point(0, 20, 10)]);
+        // 2.5 pixels per tick, negative on y
+        assert_eq!(tracker.velocity_fixed(0, 4), Some((163840, -163840)));
+        assert_eq!(tracker.velocity_fixed(0, 0), None);
+        assert_eq!(tracker.velocity_fixed(1, 4), None);
+
+        tracker.update(&[point(0, u16::MAX, 0)]);
+        assert_eq!(tracker.velocity_fixed(0, 1).map(|(x, _)| x), Some(i32::MAX));
+    }
+
+    #[test]
+    fn pressed_this_frame_is_a_rising_edge() {
+        let mut tracker = TouchTracker::new();
+        assert!(!tracker.pressed_this_frame());
+
+        tracker.update(&[point(0, 10, 20)]);
+        assert!(tracker.pressed_this_frame());
+        tracker.update(&[point(0, 11, 20)]);
+        assert!(!tracker.pressed_this_frame());
+
+        // a second finger while the first is held
+        tracker.update(&[point(0, 11, 20), point(1, 50, 50)]);
+        assert!(tracker.pressed_this_frame());
+        tracker.update(&[]);
+        assert!(!tracker.pressed_this_frame());
+    }
+
+    #[test]
+    fn press_duration_counts_frames_since_press() {
+        let mut tracker = TouchTracker::new();
+        tracker.update(&[point(0, 10, 20)]);
+        assert_eq!(tracker.press_duration(0), Some(0));
+
+        tracker.update(&[point(0, 10, 20), point(1, 50, 50)]);
+        tracker.update(&[point(0, 10, 20), point(1, 50, 50)]);
+        assert_eq!(tracker.press_duration(0), Some(2));
+        assert_eq!(tracker.press_duration(1), Some(1));
+
+        // a new press starts over
+        tracker.update(&[]);
+        assert_eq!(tracker.press_duration(0), None);
+        tracker.update(&[point(0, 10, 20)]);
+        assert_eq!(tracker.press_duration(0), Some(0));
+    }
+
+    #[test]
+    fn extrapolate_projects_forward_at_constant_velocity() {
+        let mut tracker = TouchTracker::new();
+        tracker.update(&[point(0, 100, 100)]);
+        // no previous frame yet, so the last sample is returned as is
+        let predicted = tracker.extrapolate(0, 5, 10).unwrap();
+        assert_eq!((predicted.x, predicted.y), (100, 100));
+
+        tracker.update(&[point(0, 120, 90)]);
+        let predicted = tracker.extrapolate(0, 5, 10).unwrap();
+        assert_eq!((predicted.x, predicted.y), (130, 85));
+
+        // capped at one frame ahead and clamped to the u16 range
+        let predicted = tracker.extrapolate(0, 50, 10).unwrap();
+        assert_eq!((predicted.x, predicted.y), (140, 80));
+        tracker.update(&[point(0, 10, 60)]);
+        let predicted = tracker.extrapolate(0, 10, 10).unwrap();
+        assert_eq!((predicted.x, predicted.y), (0, 30));
+
+        assert_eq!(
+            tracker.extrapolate(0, 5, 0).map(|p| (p.x, p.y)),
+            Some((10, 60))
+        );
+        assert!(tracker.extrapolate(1, 5, 10).is_none());
+    }
+}
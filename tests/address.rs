@@ -0,0 +1,71 @@
+mod common;
+
+use common::{block_on, MockDelay, MockI2c, MockPin, PinEvent, PinLog};
+use gt911::{Gt911, Gt911Blocking, GET_TOUCH_BUF_SIZE};
+
+fn pins(log: &PinLog) -> (MockPin, MockPin, MockDelay) {
+    let rst = MockPin {
+        log: log.clone(),
+        event: PinEvent::Rst,
+    };
+    let int = MockPin {
+        log: log.clone(),
+        event: PinEvent::Int,
+    };
+    let delay = MockDelay { log: log.clone() };
+    (rst, int, delay)
+}
+
+fn expected_sequence(int_level: bool) -> Vec<PinEvent> {
+    vec![
+        PinEvent::Rst(false),
+        PinEvent::DelayMs(20),
+        PinEvent::Int(int_level),
+        PinEvent::DelayMs(2),
+        PinEvent::Rst(true),
+        PinEvent::DelayMs(6),
+        PinEvent::Int(false),
+        PinEvent::DelayMs(50),
+    ]
+}
+
+#[test]
+fn change_address_to_0x14_blocking() {
+    let log = PinLog::default();
+    let (mut rst, mut int, mut delay) = pins(&log);
+
+    let touch = Gt911Blocking::default()
+        .change_address(&mut rst, &mut int, &mut delay, 0x14)
+        .unwrap();
+
+    assert_eq!(*log.borrow(), expected_sequence(true));
+
+    // the returned driver talks to the new address
+    let mut i2c = MockI2c::with_addr(0x14);
+    touch.init(&mut i2c).unwrap();
+}
+
+#[test]
+fn change_address_to_0x5d_async() {
+    let log = PinLog::default();
+    let (mut rst, mut int, mut delay) = pins(&log);
+
+    let touch =
+        block_on(Gt911::new(0x14).change_address(&mut rst, &mut int, &mut delay, 0x5D)).unwrap();
+
+    assert_eq!(*log.borrow(), expected_sequence(false));
+
+    let mut i2c = MockI2c::with_addr(0x5D);
+    let mut buf = [0u8; GET_TOUCH_BUF_SIZE];
+    block_on(touch.init(&mut i2c, &mut buf)).unwrap();
+}
+
+#[test]
+#[should_panic]
+fn change_address_rejects_unsupported_address() {
+    let log = PinLog::default();
+    let (mut rst, mut int, mut delay) = pins(&log);
+
+    let _ =
+        Gt911Blocking::<MockI2c>::default().change_address(&mut rst, &mut int, &mut delay, 0x20);
+}
@@ -0,0 +1,367 @@
+// A register level GT911 simulator used by the integration tests
+// Every register is plain memory, writes and reads auto-increment the register pointer
+// and every transaction is logged so that tests can assert on bus traffic
+
+#![allow(dead_code)]
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+use embedded_hal::i2c::{ErrorKind, ErrorType, NoAcknowledgeSource, Operation};
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+pub const ADDR: u8 = 0x5D;
+pub const PRODUCT_ID_REG: u16 = 0x8140;
+pub const STATUS_REG: u16 = 0x814E;
+pub const TOUCHPOINT_1_REG: u16 = 0x814F;
+
+/// One operation of a bus transaction as seen on the wire
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transfer {
+    Write(Vec<u8>),
+    Read(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op {
+    Write { reg: u16, data: Vec<u8> },
+    Read { reg: u16, len: usize },
+}
+
+pub struct MockI2c {
+    pub addr: u8,
+    pub regs: Vec<u8>,
+    pub log: Vec<Op>,
+    /// Every transaction on the bus, including writes that only set the register pointer
+    pub transactions: Vec<Vec<Transfer>>,
+    /// Frames the simulated controller reports next, one is loaded every time the status register is cleared
+    pub frames: VecDeque<Vec<(u8, u16, u16, u16)>>,
+    /// Values the status register takes on its next reads, simulates the controller updating the frame mid-read
+    pub status_reads: VecDeque<u8>,
+    pointer: u16,
+}
+
+impl MockI2c {
+    /// A healthy GT911 at the default address
+    pub fn new() -> Self {
+        Self::with_addr(ADDR)
+    }
+
+    pub fn with_addr(addr: u8) -> Self {
+        let mut mock = Self {
+            addr,
+            regs: vec![0u8; 0x10000],
+            log: Vec::new(),
+            transactions: Vec::new(),
+            frames: VecDeque::new(),
+            status_reads: VecDeque::new(),
+            pointer: 0,
+        };
+        mock.set(PRODUCT_ID_REG, b"911\0");
+        mock
+    }
+
+    pub fn set(&mut self, reg: u16, data: &[u8]) {
+        let start = reg as usize;
+        self.regs[start..start + data.len()].copy_from_slice(data);
+    }
+
+    pub fn get(&self, reg: u16) -> u8 {
+        self.regs[reg as usize]
+    }
+
+    /// Loads a touch frame: sets the status register (ready + count) and the touchpoint entries
+    pub fn set_frame(&mut self, points: &[(u8, u16, u16, u16)]) {
+        self.set(STATUS_REG, &[0x80 | points.len() as u8]);
+        for (n, (track_id, x, y, area)) in points.iter().enumerate() {
+            let x = x.to_le_bytes();
+            let y = y.to_le_bytes();
+            let area = area.to_le_bytes();
+            let entry = [*track_id, x[0], x[1], y[0], y[1], area[0], area[1], 0];
+            self.set(TOUCHPOINT_1_REG + (n * 8) as u16, &entry);
+        }
+    }
+
+    /// Returns true if any logged read overlapped the given register range
+    pub fn read_overlaps(&self, start: u16, len: usize) -> bool {
+        let end = start as usize + len;
+        self.log.iter().any(|op| match op {
+            Op::Read { reg, len } => {
+                (*reg as usize) < end && (*reg as usize + *len) > start as usize
+            }
+            _ => false,
+        })
+    }
+
+    fn execute(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), ErrorKind> {
+        if address != self.addr {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+
+        self.transactions.push(
+            operations
+                .iter()
+                .map(|op| match op {
+                    Operation::Write(bytes) => Transfer::Write(bytes.to_vec()),
+                    Operation::Read(buf) => Transfer::Read(buf.len()),
+                })
+                .collect(),
+        );
+        for op in operations {
+            match op {
+                Operation::Write(bytes) => {
+                    if bytes.len() >= 2 {
+                        self.pointer = u16::from_be_bytes([bytes[0], bytes[1]]);
+                        let reg = self.pointer;
+                        let data = &bytes[2..];
+                        self.set(reg, data);
+                        self.pointer = reg.wrapping_add(data.len() as u16);
+                        // a write with no data only sets the register pointer for a following read
+                        if !data.is_empty() {
+                            self.log.push(Op::Write {
+                                reg,
+                                data: data.to_vec(),
+                            });
+                        }
+                        if reg == STATUS_REG && data == [0] {
+                            if let Some(frame) = self.frames.pop_front() {
+                                self.set_frame(&frame);
+                            }
+                        }
+                    }
+                }
+                Operation::Read(buf) => {
+                    let reg = self.pointer;
+                    if reg == STATUS_REG {
+                        if let Some(status) = self.status_reads.pop_front() {
+                            self.set(STATUS_REG, &[status]);
+                        }
+                    }
+                    let start = reg as usize;
+                    buf.copy_from_slice(&self.regs[start..start + buf.len()]);
+                    self.pointer = reg.wrapping_add(buf.len() as u16);
+                    self.log.push(Op::Read {
+                        reg,
+                        len: buf.len(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ErrorType for MockI2c {
+    type Error = ErrorKind;
+}
+
+impl embedded_hal::i2c::I2c for MockI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.execute(address, operations)
+    }
+}
+
+impl embedded_hal_async::i2c::I2c for MockI2c {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.execute(address, operations)
+    }
+}
+
+/// Several simulated devices sharing one bus, transactions go to the device with the matching address
+pub struct MockBus {
+    pub devices: Vec<MockI2c>,
+}
+
+impl MockBus {
+    pub fn device(&mut self, addr: u8) -> &mut MockI2c {
+        self.devices
+            .iter_mut()
+            .find(|device| device.addr == addr)
+            .unwrap()
+    }
+
+    fn execute(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), ErrorKind> {
+        match self
+            .devices
+            .iter_mut()
+            .find(|device| device.addr == address)
+        {
+            Some(device) => device.execute(address, operations),
+            None => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+        }
+    }
+}
+
+impl ErrorType for MockBus {
+    type Error = ErrorKind;
+}
+
+impl embedded_hal::i2c::I2c for MockBus {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.execute(address, operations)
+    }
+}
+
+impl embedded_hal_async::i2c::I2c for MockBus {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.execute(address, operations)
+    }
+}
+
+/// Records pin and delay activity in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinEvent {
+    Rst(bool),
+    Int(bool),
+    DelayMs(u32),
+}
+
+pub type PinLog = Rc<RefCell<Vec<PinEvent>>>;
+
+pub struct MockPin {
+    pub log: PinLog,
+    pub event: fn(bool) -> PinEvent,
+}
+
+impl embedded_hal::digital::ErrorType for MockPin {
+    type Error = Infallible;
+}
+
+impl embedded_hal::digital::OutputPin for MockPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.log.borrow_mut().push((self.event)(false));
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.log.borrow_mut().push((self.event)(true));
+        Ok(())
+    }
+}
+
+pub struct MockDelay {
+    pub log: PinLog,
+}
+
+impl embedded_hal::delay::DelayNs for MockDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.log
+            .borrow_mut()
+            .push(PinEvent::DelayMs(ns / 1_000_000));
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.log.borrow_mut().push(PinEvent::DelayMs(ms));
+    }
+}
+
+impl embedded_hal_async::delay::DelayNs for MockDelay {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.log
+            .borrow_mut()
+            .push(PinEvent::DelayMs(ns / 1_000_000));
+    }
+
+    async fn delay_ms(&mut self, ms: u32) {
+        self.log.borrow_mut().push(PinEvent::DelayMs(ms));
+    }
+}
+
+/// An INT line that never falls again, waiting on it never completes
+pub struct IdleInt;
+
+impl embedded_hal::digital::ErrorType for IdleInt {
+    type Error = Infallible;
+}
+
+impl embedded_hal_async::digital::Wait for IdleInt {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        core::future::pending().await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        core::future::pending().await
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        core::future::pending().await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        core::future::pending().await
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        core::future::pending().await
+    }
+}
+
+/// An INT line that falls right away every time it is waited on, as if a new frame is always pending
+pub struct FallingInt;
+
+impl embedded_hal::digital::ErrorType for FallingInt {
+    type Error = Infallible;
+}
+
+impl embedded_hal_async::digital::Wait for FallingInt {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Polls a future once, None if it is not ready yet
+pub fn poll_once<F: Future>(future: F) -> Option<F::Output> {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    match future.as_mut().poll(&mut cx) {
+        Poll::Ready(output) => Some(output),
+        Poll::Pending => None,
+    }
+}
+
+/// Minimal executor for driving the async driver to completion in tests
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
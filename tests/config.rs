@@ -0,0 +1,122 @@
+mod common;
+
+use common::{MockI2c, Op};
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, Operation};
+use gt911::{Error, Gt911Blocking, CONFIG_LEN};
+
+const CONFIG_START_REG: u16 = 0x8047;
+
+// a config block with every byte set to its offset
+fn config() -> Vec<u8> {
+    (0..CONFIG_LEN).map(|offset| offset as u8).collect()
+}
+
+fn checksum(config: &[u8]) -> u8 {
+    let sum = config.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    sum.wrapping_neg()
+}
+
+// the config block as the driver writes it: config, checksum (0x80FF) and fresh flag (0x8100)
+fn expected_write(config: &[u8]) -> Op {
+    let mut data = config.to_vec();
+    data.push(checksum(config));
+    data.push(1);
+    Op::Write {
+        reg: CONFIG_START_REG,
+        data,
+    }
+}
+
+fn config_writes(i2c: &MockI2c) -> Vec<&Op> {
+    i2c.log
+        .iter()
+        .filter(|op| matches!(op, Op::Write { .. }))
+        .collect()
+}
+
+#[test]
+fn config_setter_only_changes_its_bits() {
+    let mut i2c = MockI2c::new();
+    let mut config = config();
+    // Low_Power_Control (0x8055), the upper nibble is not the idle timeout
+    config[0x0E] = 0xA3;
+    i2c.set(CONFIG_START_REG, &config);
+
+    Gt911Blocking::default()
+        .set_idle_timeout(&mut i2c, 5)
+        .unwrap();
+
+    config[0x0E] = 0xA5;
+    assert_eq!(config_writes(&i2c), [&expected_write(&config)]);
+}
+
+#[test]
+fn refresh_rate_round_trips() {
+    let mut i2c = MockI2c::new();
+    let mut config = config();
+    // Refresh_Rate (0x8056), the upper nibble is not the report period
+    config[0x0F] = 0x50;
+    i2c.set(CONFIG_START_REG, &config);
+    let touch = Gt911Blocking::default();
+
+    touch.set_refresh_rate(&mut i2c, 9).unwrap();
+
+    assert_eq!(touch.read_refresh_rate(&mut i2c).unwrap(), 9);
+    config[0x0F] = 0x59;
+    assert_eq!(config_writes(&i2c), [&expected_write(&config)]);
+}
+
+#[test]
+fn ensure_config_writes_only_when_different() {
+    let mut i2c = MockI2c::new();
+    let mut config = config();
+    i2c.set(CONFIG_START_REG, &config);
+    let touch = Gt911Blocking::default();
+
+    assert!(!touch.ensure_config(&mut i2c, &config).unwrap());
+    assert!(config_writes(&i2c).is_empty());
+
+    config[0x10] = 0x55;
+    assert!(touch.ensure_config(&mut i2c, &config).unwrap());
+    assert_eq!(config_writes(&i2c), [&expected_write(&config)]);
+}
+
+#[test]
+fn verified_write_checks_the_checksum_read_back() {
+    let mut i2c = MockI2c::new();
+    let config = config();
+
+    Gt911Blocking::default()
+        .write_config_verified(&mut i2c, &config)
+        .unwrap();
+
+    assert_eq!(config_writes(&i2c), [&expected_write(&config)]);
+    assert_eq!(i2c.get(0x80FF), checksum(&config));
+}
+
+// a controller that rejects every config write, its checksum register always reads 0
+struct RejectingI2c(MockI2c);
+
+impl ErrorType for RejectingI2c {
+    type Error = ErrorKind;
+}
+
+impl I2c for RejectingI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.0.set(0x80FF, &[0]);
+        self.0.transaction(address, operations)
+    }
+}
+
+#[test]
+fn verified_write_reports_a_rejected_config() {
+    let mut i2c = RejectingI2c(MockI2c::new());
+
+    let result = Gt911Blocking::default().write_config_verified(&mut i2c, &config());
+
+    assert!(matches!(result, Err(Error::ConfigChecksum)));
+}
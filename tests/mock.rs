@@ -0,0 +1,53 @@
+mod common;
+
+use common::block_on;
+use gt911::{Gt911, Gt911Blocking, Gt911Mock, Point, GET_MULTITOUCH_BUF_SIZE};
+
+fn point(track_id: u8, x: u16, y: u16, area: u16) -> Point {
+    Point {
+        track_id,
+        x,
+        y,
+        area,
+    }
+}
+
+#[test]
+fn mock_reports_scripted_frames_blocking() {
+    let mut i2c = Gt911Mock::default();
+    let touch = Gt911Blocking::default();
+    touch.init(&mut i2c).unwrap();
+
+    assert!(matches!(
+        touch.get_multi_touch(&mut i2c),
+        Err(gt911::Error::NotReady)
+    ));
+
+    let points = [point(0, 100, 200, 30), point(1, 300, 400, 31)];
+    i2c.set_touches(&points);
+    assert_eq!(touch.get_multi_touch(&mut i2c).unwrap(), points);
+    assert!(!i2c.is_ready());
+
+    i2c.set_release();
+    assert_eq!(touch.get_touch(&mut i2c).unwrap(), None);
+}
+
+#[test]
+fn mock_reports_scripted_frames_async() {
+    let mut i2c = Gt911Mock::default();
+    let touch = Gt911::default();
+    let mut buf = [0u8; GET_MULTITOUCH_BUF_SIZE];
+    block_on(touch.init(&mut i2c, &mut buf)).unwrap();
+
+    i2c.set_touches(&[point(3, 10, 20, 5)]);
+    let points = block_on(touch.get_multi_touch(&mut i2c, &mut buf)).unwrap();
+    assert_eq!(points, [point(3, 10, 20, 5)]);
+    assert!(!i2c.is_ready());
+}
+
+#[test]
+fn mock_does_not_answer_other_addresses() {
+    let mut i2c = Gt911Mock::new(gt911::ADDR_14);
+    let touch = Gt911Blocking::new(gt911::ADDR_5D);
+    assert!(!touch.is_present(&mut i2c).unwrap());
+}
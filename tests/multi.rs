@@ -0,0 +1,49 @@
+mod common;
+
+use common::{MockBus, MockI2c, STATUS_REG};
+use gt911::{Gt911, Gt911Blocking, TouchTracker, ADDR_14, ADDR_5D};
+
+#[test]
+fn two_devices_on_one_bus_are_independent() {
+    let mut bus = MockBus {
+        devices: vec![MockI2c::with_addr(ADDR_5D), MockI2c::with_addr(ADDR_14)],
+    };
+    bus.device(ADDR_5D).set_frame(&[(0, 100, 200, 30)]);
+    bus.device(ADDR_14)
+        .set_frame(&[(0, 300, 400, 40), (1, 500, 600, 50)]);
+
+    let touches = [Gt911Blocking::new(ADDR_5D), Gt911Blocking::new(ADDR_14)];
+    let mut trackers = [TouchTracker::new(), TouchTracker::new()];
+
+    Gt911Blocking::poll_many(&touches, &mut bus, |index, result| {
+        trackers[index].update(&result.unwrap());
+    });
+
+    assert_eq!(trackers[0].history(0), Some(&[(100, 200)][..]));
+    assert_eq!(trackers[0].history(1), None);
+    assert_eq!(trackers[1].history(0), Some(&[(300, 400)][..]));
+    assert_eq!(trackers[1].peak_area(1), Some(50));
+
+    // a new frame on one device only
+    bus.device(ADDR_14).set_frame(&[(0, 310, 410, 40)]);
+    let mut polled = Vec::new();
+    Gt911Blocking::poll_many(&touches, &mut bus, |index, result| {
+        trackers[index].update(&result.unwrap());
+        polled.push(index);
+    });
+
+    assert_eq!(polled, [1]);
+    assert_eq!(bus.device(ADDR_5D).get(STATUS_REG), 0);
+    assert_eq!(trackers[0].history(0), Some(&[(100, 200)][..]));
+    assert_eq!(trackers[1].history(0), Some(&[(300, 400), (310, 410)][..]));
+    assert_eq!(trackers[1].history(1), None);
+}
+
+fn assert_sync<T: Sync + Send>() {}
+
+#[test]
+fn drivers_can_be_shared_between_tasks() {
+    // e.g. a static driver or an &Gt911 held across .await in a Send task
+    assert_sync::<Gt911Blocking<MockI2c>>();
+    assert_sync::<Gt911<MockI2c>>();
+}
@@ -0,0 +1,75 @@
+use gt911::{Point, TouchFrame};
+
+fn point(track_id: u8, x: u16, y: u16, area: u16) -> Point {
+    Point {
+        track_id,
+        x,
+        y,
+        area,
+    }
+}
+
+#[test]
+fn centroid_of_no_points_is_none() {
+    assert_eq!(Point::centroid(&[]), None);
+}
+
+#[test]
+fn centroid_rounds_to_nearest() {
+    let points = [point(2, 10, 20, 30), point(1, 11, 23, 31)];
+    // 10.5, 21.5 and 30.5 round up
+    assert_eq!(Point::centroid(&points), Some(point(1, 11, 22, 31)));
+
+    let points = [point(0, 0, 0, 0), point(1, 1, 1, 1), point(2, 1, 1, 1)];
+    // 0.67 rounds up
+    assert_eq!(Point::centroid(&points), Some(point(0, 1, 1, 1)));
+}
+
+#[test]
+fn centroid_does_not_overflow() {
+    let points = vec![point(0, u16::MAX, u16::MAX, u16::MAX); 5];
+    assert_eq!(
+        Point::centroid(&points),
+        Some(point(0, u16::MAX, u16::MAX, u16::MAX))
+    );
+}
+
+#[test]
+fn touch_frame_formats_on_one_line() {
+    let points = [point(0, 10, 20, 1), point(1, 30, 40, 1)];
+    assert_eq!(TouchFrame(&points).to_string(), "2pts: #0(10,20) #1(30,40)");
+    assert_eq!(TouchFrame(&[]).to_string(), "0pts:");
+}
+
+#[test]
+fn pinch_distance_needs_two_points() {
+    assert_eq!(Point::pinch_distance(&[]), None);
+    assert_eq!(Point::pinch_distance(&[point(0, 1, 2, 3)]), None);
+}
+
+#[test]
+fn pinch_distance_is_rounded_down() {
+    let points = [point(0, 10, 10, 1), point(1, 13, 14, 1), point(2, 0, 0, 1)];
+    assert_eq!(Point::pinch_distance(&points), Some(5));
+
+    // sqrt(2) = 1.41
+    let points = [point(0, 11, 10, 1), point(1, 10, 11, 1)];
+    assert_eq!(Point::pinch_distance(&points), Some(1));
+
+    let points = [point(0, 0, 0, 1), point(1, u16::MAX, u16::MAX, 1)];
+    assert_eq!(Point::pinch_distance(&points), Some(92680));
+}
+
+#[test]
+fn pinch_distance_around_perfect_squares() {
+    let distance = |dx: u16| Point::pinch_distance(&[point(0, 0, 0, 0), point(1, dx, 0, 0)]);
+    assert_eq!(distance(0), Some(0));
+    assert_eq!(distance(1), Some(1));
+    assert_eq!(distance(255), Some(255));
+    assert_eq!(distance(256), Some(256));
+    // sqrt(24^2 + 1) is just above 24
+    assert_eq!(
+        Point::pinch_distance(&[point(0, 0, 0, 0), point(1, 24, 1, 0)]),
+        Some(24)
+    );
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::{MockI2c, Op, STATUS_REG, TOUCHPOINT_1_REG};
+use gt911::{Gt911Blocking, Poll, TouchPoller};
+
+fn status_read() -> Op {
+    Op::Read {
+        reg: STATUS_REG,
+        len: 1,
+    }
+}
+
+fn status_clear() -> Op {
+    Op::Write {
+        reg: STATUS_REG,
+        data: vec![0],
+    }
+}
+
+#[test]
+fn every_poll_does_one_transaction() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 1, 2, 3), (1, 4, 5, 6)]);
+    let touch = Gt911Blocking::default();
+    let mut poller = TouchPoller::new(&touch);
+
+    assert!(matches!(poller.poll(&mut i2c), Poll::Pending));
+    assert_eq!(i2c.log, [status_read()]);
+    assert!(matches!(poller.poll(&mut i2c), Poll::Pending));
+    assert_eq!(i2c.log.len(), 2);
+    assert_eq!(
+        i2c.log[1],
+        Op::Read {
+            reg: TOUCHPOINT_1_REG,
+            len: 16
+        }
+    );
+
+    match poller.poll(&mut i2c) {
+        Poll::Ready(Ok(points)) => assert_eq!(points.len(), 2),
+        other => panic!("unexpected {:?}", other),
+    }
+    assert_eq!(i2c.log.len(), 3);
+    assert_eq!(i2c.log[2], status_clear());
+}
+
+#[test]
+fn release_frame_skips_the_touch_points() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[]);
+    let touch = Gt911Blocking::default();
+    let mut poller = TouchPoller::new(&touch);
+
+    assert!(matches!(poller.poll(&mut i2c), Poll::Pending));
+    match poller.poll(&mut i2c) {
+        Poll::Ready(Ok(points)) => assert!(points.is_empty()),
+        other => panic!("unexpected {:?}", other),
+    }
+    assert_eq!(i2c.log, [status_read(), status_clear()]);
+}
+
+#[test]
+fn torn_frame_recheck_starts_over() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 1, 2, 3), (1, 4, 5, 6)]);
+    // the count changes between the first status read and its recheck
+    i2c.status_reads.extend([0x81, 0x82]);
+    let touch = Gt911Blocking::default().with_torn_frame_check(true);
+    let mut poller = TouchPoller::new(&touch);
+
+    // status, points, recheck (torn) and again status, points, recheck (consistent)
+    for _ in 0..6 {
+        assert!(matches!(poller.poll(&mut i2c), Poll::Pending));
+    }
+    match poller.poll(&mut i2c) {
+        Poll::Ready(Ok(points)) => assert_eq!(points.len(), 2),
+        other => panic!("unexpected {:?}", other),
+    }
+    assert_eq!(i2c.log.len(), 7);
+    assert_eq!(i2c.log[3], status_read());
+}
+
+#[test]
+fn reset_returns_to_reading_the_status() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 1, 2, 3)]);
+    let touch = Gt911Blocking::default();
+    let mut poller = TouchPoller::new(&touch);
+
+    assert!(matches!(poller.poll(&mut i2c), Poll::Pending));
+    poller.reset();
+    assert!(matches!(poller.poll(&mut i2c), Poll::Pending));
+
+    assert_eq!(i2c.log, [status_read(), status_read()]);
+}
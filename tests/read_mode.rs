@@ -0,0 +1,72 @@
+mod common;
+
+use common::{block_on, MockI2c, Transfer};
+use gt911::{Gt911, Gt911Blocking, ReadMode, GET_TOUCH_BUF_SIZE};
+
+fn read_slot(touch: &Gt911Blocking<MockI2c>) -> Vec<Vec<Transfer>> {
+    let mut i2c = MockI2c::new();
+    let mut buf = [0u8; 8];
+    touch.read_touchpoint_raw(&mut i2c, 0, &mut buf).unwrap();
+    i2c.transactions
+}
+
+#[test]
+fn combined_read_is_one_write_read() {
+    let transactions = read_slot(&Gt911Blocking::default());
+
+    assert_eq!(
+        transactions,
+        [vec![Transfer::Write(vec![0x81, 0x4F]), Transfer::Read(8)]]
+    );
+}
+
+#[test]
+fn split_read_is_a_write_then_a_read() {
+    let transactions = read_slot(&Gt911Blocking::default().with_read_mode(ReadMode::Split));
+
+    assert_eq!(
+        transactions,
+        [
+            vec![Transfer::Write(vec![0x81, 0x4F])],
+            vec![Transfer::Read(8)]
+        ]
+    );
+}
+
+#[test]
+fn split_read_with_dummy_reads_one_extra_byte_first() {
+    let touch = Gt911Blocking::default().with_read_mode(ReadMode::SplitWithDummy);
+    let transactions = read_slot(&touch);
+
+    assert_eq!(
+        transactions,
+        [
+            vec![Transfer::Write(vec![0x81, 0x4F])],
+            vec![Transfer::Read(1), Transfer::Read(8)]
+        ]
+    );
+}
+
+#[test]
+fn split_read_async() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 1, 2, 3)]);
+    i2c.transactions.clear();
+
+    let touch = Gt911::default().with_read_mode(ReadMode::Split);
+    let mut buf = [0u8; GET_TOUCH_BUF_SIZE];
+    let point = block_on(touch.get_touch(&mut i2c, &mut buf))
+        .unwrap()
+        .unwrap();
+
+    assert_eq!((point.x, point.y), (1, 2));
+    assert_eq!(
+        i2c.transactions[..4],
+        [
+            vec![Transfer::Write(vec![0x81, 0x4E])],
+            vec![Transfer::Read(1)],
+            vec![Transfer::Write(vec![0x81, 0x4E])],
+            vec![Transfer::Read(GET_TOUCH_BUF_SIZE)]
+        ]
+    );
+}
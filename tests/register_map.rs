@@ -0,0 +1,96 @@
+mod common;
+
+use common::{block_on, MockI2c, Op, Transfer};
+use gt911::{Gt911, Gt911Blocking, RegisterMap, GET_MULTITOUCH_BUF_SIZE};
+
+// custom firmware with the touch points moved away from the status register
+const REMAPPED: RegisterMap = RegisterMap {
+    command: 0x8040,
+    product_id: 0x8140,
+    status: 0x8200,
+    touchpoint_1: 0x8210,
+};
+
+fn remapped_frame() -> MockI2c {
+    let mut i2c = MockI2c::new();
+    i2c.set(0x8200, &[0x81]);
+    i2c.set(0x8210, &[0x02, 0x10, 0x00, 0x20, 0x00, 0x05, 0x00, 0x00]);
+    i2c
+}
+
+#[test]
+fn standard_map_reads_status_and_points_in_one_read() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 1, 2, 3)]);
+    i2c.transactions.clear();
+
+    let mut buf = [0u8; GET_MULTITOUCH_BUF_SIZE];
+    block_on(Gt911::default().get_multi_touch(&mut i2c, &mut buf)).unwrap();
+
+    // the status poll, then the status together with the one reported touch point
+    assert_eq!(
+        i2c.transactions[..2],
+        [
+            vec![Transfer::Write(vec![0x81, 0x4E]), Transfer::Read(1)],
+            vec![Transfer::Write(vec![0x81, 0x4E]), Transfer::Read(9)]
+        ]
+    );
+}
+
+#[test]
+fn remapped_registers_take_two_reads() {
+    let mut i2c = remapped_frame();
+
+    let touch = Gt911::default().with_register_map(REMAPPED);
+    let mut buf = [0u8; GET_MULTITOUCH_BUF_SIZE];
+    let points = block_on(touch.get_multi_touch(&mut i2c, &mut buf)).unwrap();
+
+    assert_eq!(
+        (points[0].track_id, points[0].x, points[0].y),
+        (2, 0x10, 0x20)
+    );
+    assert_eq!(
+        i2c.log,
+        [
+            Op::Read {
+                reg: 0x8200,
+                len: 1
+            },
+            Op::Read {
+                reg: 0x8210,
+                len: 8
+            },
+            Op::Write {
+                reg: 0x8200,
+                data: vec![0]
+            },
+        ]
+    );
+}
+
+#[test]
+fn remapped_registers_blocking() {
+    let mut i2c = remapped_frame();
+
+    let touch = Gt911Blocking::default().with_register_map(REMAPPED);
+    let point = touch.get_touch(&mut i2c).unwrap().unwrap();
+
+    assert_eq!((point.track_id, point.x, point.y), (2, 0x10, 0x20));
+    assert_eq!(
+        i2c.log,
+        [
+            Op::Read {
+                reg: 0x8200,
+                len: 1
+            },
+            Op::Read {
+                reg: 0x8210,
+                len: 8
+            },
+            Op::Write {
+                reg: 0x8200,
+                data: vec![0]
+            },
+        ]
+    );
+}
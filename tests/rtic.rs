@@ -0,0 +1,33 @@
+// Emulates an RTIC shared resource where the i2c bus is only borrowed inside a lock closure
+
+mod common;
+
+use common::{MockI2c, Op, STATUS_REG};
+use core::cell::RefCell;
+use gt911::{Error, Gt911Blocking};
+
+struct Shared<T>(RefCell<T>);
+
+impl<T> Shared<T> {
+    fn lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.0.borrow_mut())
+    }
+}
+
+#[test]
+fn get_touch_under_resource_lock() {
+    let i2c = Shared(RefCell::new(MockI2c::new()));
+    let touch = Gt911Blocking::default();
+
+    i2c.lock(|i2c| touch.init(i2c)).unwrap();
+    i2c.lock(|i2c| i2c.set_frame(&[(0, 10, 20, 5)]));
+
+    // another task uses the bus in-between the lock sections of the touch task
+    let point = i2c.lock(|i2c| touch.get_touch(i2c)).unwrap().unwrap();
+    i2c.lock(|i2c| i2c.log.push(Op::Read { reg: 0, len: 1 }));
+    let next = i2c.lock(|i2c| touch.get_touch(i2c));
+
+    assert_eq!((point.x, point.y), (10, 20));
+    assert!(matches!(next, Err(Error::NotReady)));
+    assert_eq!(i2c.lock(|i2c| i2c.get(STATUS_REG)), 0);
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::{block_on, MockI2c};
+use gt911::{Gt911, Gt911Blocking, CONFIG_BUF_SIZE};
+
+// a healthy controller: firmware 0x1060, 800x480 in both the product info and the config
+fn healthy() -> MockI2c {
+    let mut i2c = MockI2c::new();
+    i2c.set(0x8144, &[0x60, 0x10, 0x20, 0x03, 0xE0, 0x01]);
+    i2c.set(0x8048, &[0x20, 0x03, 0xE0, 0x01]);
+    // two's complement of the sum of the config block (0x20 + 0x03 + 0xE0 + 0x01 = 0x04 as u8)
+    i2c.set(0x80FF, &[0xFC]);
+    i2c
+}
+
+#[test]
+fn self_test_passes_on_healthy_controller() {
+    let mut i2c = healthy();
+    let report = Gt911Blocking::default().self_test(&mut i2c).unwrap();
+
+    assert!(report.passed());
+    assert_eq!(report.version.firmware_version, 0x1060);
+}
+
+#[test]
+fn self_test_reports_bad_checksum_async() {
+    let mut i2c = healthy();
+    i2c.set(0x80FF, &[0x00]);
+
+    let mut buf = [0u8; CONFIG_BUF_SIZE];
+    let report = block_on(Gt911::default().self_test(&mut i2c, &mut buf)).unwrap();
+
+    assert!(!report.passed());
+    assert!(!report.config_checksum_ok);
+    assert!(report.product_id_ok && report.firmware_ok && report.resolution_ok);
+}
+
+#[test]
+fn probe_and_version_info_share_the_product_id_str() {
+    let mut i2c = healthy();
+    let touch = Gt911Blocking::default();
+    let probe = touch.probe(&mut i2c).unwrap();
+    let version = touch.read_version_info(&mut i2c).unwrap();
+
+    assert_eq!(probe.product_id_str(), Some("911"));
+    assert_eq!(version.product_id_str(), Some("911"));
+    assert_eq!(version.config_checksum, 0xFC);
+
+    i2c.set(0x8140, &[0xFF, b'9', b'1', b'1']);
+    assert_eq!(touch.probe(&mut i2c).unwrap().product_id_str(), None);
+    assert_eq!(
+        touch.read_version_info(&mut i2c).unwrap().product_id_str(),
+        None
+    );
+}
@@ -0,0 +1,443 @@
+mod common;
+
+use common::{block_on, MockI2c, Op, STATUS_REG, TOUCHPOINT_1_REG};
+use gt911::{
+    Error, ExclusionZone, Gt911, Gt911Blocking, ALL_SLOTS_LEN, GET_MULTITOUCH_BUF_SIZE,
+    MAX_EXCLUSION_ZONES,
+};
+
+#[test]
+fn release_frame_does_not_read_coordinates_blocking() {
+    let mut i2c = MockI2c::new();
+    // stale data from a previous touch is still sitting in the touchpoint registers
+    i2c.set_frame(&[(0, 100, 200, 30), (1, 300, 400, 30)]);
+    i2c.set(STATUS_REG, &[0x80]);
+    i2c.log.clear();
+
+    let touch = Gt911Blocking::default();
+    let points = touch.get_multi_touch(&mut i2c).unwrap();
+
+    assert!(points.is_empty());
+    assert!(!i2c.read_overlaps(TOUCHPOINT_1_REG, 40));
+    assert_eq!(i2c.get(STATUS_REG), 0);
+}
+
+#[test]
+fn release_frame_does_not_read_coordinates_async() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 100, 200, 30), (1, 300, 400, 30)]);
+    i2c.set(STATUS_REG, &[0x80]);
+    i2c.log.clear();
+
+    let touch = Gt911::default();
+    let mut buf = [0xAAu8; GET_MULTITOUCH_BUF_SIZE];
+    let points = block_on(touch.get_multi_touch(&mut i2c, &mut buf)).unwrap();
+
+    assert!(points.is_empty());
+    assert!(!i2c.read_overlaps(TOUCHPOINT_1_REG, 40));
+    assert_eq!(i2c.get(STATUS_REG), 0);
+}
+
+#[test]
+fn not_ready_poll_reads_only_the_status_async() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 100, 200, 30)]);
+    i2c.set(STATUS_REG, &[0x00]);
+    i2c.log.clear();
+
+    let touch = Gt911::default();
+    let mut buf = [0u8; GET_MULTITOUCH_BUF_SIZE];
+    let result = block_on(touch.get_multi_touch(&mut i2c, &mut buf));
+
+    assert!(matches!(result, Err(Error::NotReady)));
+    assert_eq!(
+        i2c.log,
+        [Op::Read {
+            reg: STATUS_REG,
+            len: 1
+        }]
+    );
+}
+
+#[test]
+fn status_and_coordinates_read_in_one_transfer_async() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 100, 200, 30), (1, 300, 400, 31)]);
+    i2c.log.clear();
+
+    let touch = Gt911::default();
+    let mut buf = [0u8; GET_MULTITOUCH_BUF_SIZE];
+    let points = block_on(touch.get_multi_touch(&mut i2c, &mut buf)).unwrap();
+
+    assert_eq!(points.len(), 2);
+    assert_eq!((points[1].x, points[1].y, points[1].area), (300, 400, 31));
+    // the status poll, then the status again together with exactly the reported touch points
+    let reads: Vec<_> = i2c
+        .log
+        .iter()
+        .filter(|op| matches!(op, Op::Read { .. }))
+        .collect();
+    assert_eq!(
+        reads,
+        [
+            &Op::Read {
+                reg: STATUS_REG,
+                len: 1
+            },
+            &Op::Read {
+                reg: STATUS_REG,
+                len: 17
+            }
+        ]
+    );
+}
+
+#[test]
+fn status_with_all_bits_set_is_clamped_blocking() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[
+        (0, 1, 2, 3),
+        (1, 4, 5, 6),
+        (2, 7, 8, 9),
+        (3, 1, 2, 3),
+        (4, 4, 5, 6),
+    ]);
+    i2c.set(STATUS_REG, &[0xFF]);
+    i2c.log.clear();
+
+    let touch = Gt911Blocking::default();
+    let points = touch.get_multi_touch(&mut i2c).unwrap();
+
+    assert_eq!(points.len(), 5);
+    assert!(!i2c.read_overlaps(TOUCHPOINT_1_REG + 40, 0x100));
+}
+
+#[test]
+fn status_with_all_bits_set_is_clamped_async() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[
+        (0, 1, 2, 3),
+        (1, 4, 5, 6),
+        (2, 7, 8, 9),
+        (3, 1, 2, 3),
+        (4, 4, 5, 6),
+    ]);
+    i2c.set(STATUS_REG, &[0xFF]);
+    i2c.log.clear();
+
+    let touch = Gt911::default();
+    let mut buf = [0u8; GET_MULTITOUCH_BUF_SIZE];
+    let points = block_on(touch.get_multi_touch(&mut i2c, &mut buf)).unwrap();
+
+    assert_eq!(points.len(), 5);
+    assert!(!i2c.read_overlaps(TOUCHPOINT_1_REG + 40, 0x100));
+}
+
+#[test]
+fn touchpoint_entry_is_little_endian() {
+    let mut i2c = MockI2c::new();
+    i2c.set(STATUS_REG, &[0x81]);
+    // track_id, x_lo, x_hi, y_lo, y_hi, area_lo, area_hi, reserved
+    i2c.set(
+        TOUCHPOINT_1_REG,
+        &[0x07, 0x34, 0x12, 0x78, 0x01, 0xCD, 0x00, 0xEE],
+    );
+
+    let touch = Gt911Blocking::default();
+    let point = touch.get_touch(&mut i2c).unwrap().unwrap();
+
+    assert_eq!(
+        (point.track_id, point.x, point.y, point.area),
+        (0x07, 0x1234, 0x0178, 0x00CD)
+    );
+}
+
+#[test]
+fn offset_is_added_and_clamped() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 5, 65530, 1)]);
+
+    let touch = Gt911Blocking::default().with_offset(-10, 10);
+    let point = touch.get_touch(&mut i2c).unwrap().unwrap();
+
+    assert_eq!((point.x, point.y), (0, u16::MAX));
+}
+
+#[test]
+fn points_in_exclusion_zones_are_dropped() {
+    static ZONES: [ExclusionZone; 2] = [
+        ExclusionZone {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 480,
+        },
+        ExclusionZone {
+            x: 0,
+            y: 470,
+            width: 800,
+            height: 10,
+        },
+    ];
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 5, 100, 1), (1, 400, 475, 1), (2, 10, 469, 1)]);
+
+    let touch = Gt911Blocking::default().with_exclusion_zones(&ZONES);
+    let points = touch.get_multi_touch(&mut i2c).unwrap();
+
+    assert_eq!(points.len(), 1);
+    assert_eq!((points[0].x, points[0].y), (10, 469));
+}
+
+#[test]
+fn consistent_touch_rechecks_status() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(3, 10, 20, 30)]);
+    i2c.log.clear();
+
+    let touch = Gt911Blocking::default();
+    let point = touch.get_touch_consistent(&mut i2c, 2).unwrap().unwrap();
+
+    assert_eq!((point.track_id, point.x, point.y), (3, 10, 20));
+    let status_reads = i2c
+        .log
+        .iter()
+        .filter(|op| matches!(op, Op::Read { reg, .. } if *reg == STATUS_REG))
+        .count();
+    assert_eq!(status_reads, 2);
+}
+
+#[test]
+fn capture_frames_times_out_without_frames() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 1, 2, 3)]);
+
+    let touch = Gt911Blocking::default();
+    let mut buf = [0u8; 2 * GET_MULTITOUCH_BUF_SIZE];
+    // the first frame is captured, the second never becomes ready
+    let result = touch.capture_frames(&mut i2c, 2, &mut buf);
+
+    assert!(matches!(result, Err(Error::Timeout)));
+    assert_eq!(&buf[..2], &[0x81, 0]);
+}
+
+#[test]
+fn torn_frame_is_read_again() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 1, 2, 3), (1, 4, 5, 6)]);
+    // the count changes between the first read and its recheck, then stays
+    i2c.status_reads.extend([0x81, 0x82]);
+
+    let touch = Gt911Blocking::default();
+    let point = touch.get_touch_consistent(&mut i2c, 1).unwrap().unwrap();
+
+    assert_eq!((point.track_id, point.x), (0, 1));
+    let status_reads = i2c
+        .log
+        .iter()
+        .filter(|op| matches!(op, Op::Read { reg, .. } if *reg == STATUS_REG))
+        .count();
+    assert_eq!(status_reads, 4);
+}
+
+#[test]
+fn torn_frame_fails_once_retries_run_out() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 1, 2, 3), (1, 4, 5, 6)]);
+    i2c.status_reads.extend([0x81, 0x82, 0x81, 0x82]);
+
+    let touch = Gt911Blocking::default();
+    let result = touch.get_touch_consistent(&mut i2c, 1);
+
+    assert!(matches!(result, Err(Error::TornFrame)));
+    // the frame was not consumed
+    assert!(!i2c
+        .log
+        .iter()
+        .any(|op| matches!(op, Op::Write { reg, .. } if *reg == STATUS_REG)));
+}
+
+#[test]
+fn torn_frame_check_reports_not_ready_async() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 1, 2, 3), (1, 4, 5, 6)]);
+    // every read of the frame is torn
+    i2c.status_reads.extend([0x81, 0x82].repeat(4));
+
+    let touch = Gt911::default().with_torn_frame_check(true);
+    let mut buf = [0u8; GET_MULTITOUCH_BUF_SIZE];
+    let result = block_on(touch.get_multi_touch(&mut i2c, &mut buf));
+
+    assert!(matches!(result, Err(Error::NotReady)));
+}
+
+#[test]
+fn get_touches_clamps_to_capacity() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 1, 2, 3), (1, 4, 5, 6), (2, 7, 8, 9), (3, 1, 2, 3)]);
+    i2c.log.clear();
+
+    let touch = Gt911Blocking::default();
+    let points = touch.get_touches::<2>(&mut i2c).unwrap();
+
+    assert_eq!(points.len(), 2);
+    assert_eq!((points[0].track_id, points[1].track_id), (0, 1));
+    // only the first two slots are read and the frame is still consumed
+    assert!(!i2c.read_overlaps(TOUCHPOINT_1_REG + 16, 0x100));
+    assert_eq!(
+        i2c.log.last(),
+        Some(&Op::Write {
+            reg: STATUS_REG,
+            data: vec![0]
+        })
+    );
+}
+
+#[test]
+fn truncated_entry_is_a_short_read() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 1, 2, 3), (1, 4, 5, 6)]);
+    // the second entry never made it off the bus
+    i2c.set(TOUCHPOINT_1_REG + 8, &[0xFF; 8]);
+
+    let touch = Gt911::default();
+    let mut buf = [0u8; GET_MULTITOUCH_BUF_SIZE];
+    let result = block_on(touch.get_multi_touch(&mut i2c, &mut buf));
+
+    assert!(matches!(result, Err(Error::ShortRead)));
+}
+
+#[test]
+fn auto_clear_off_leaves_the_frame_ready() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 1, 2, 3)]);
+
+    let touch = Gt911Blocking::default().with_auto_clear(false);
+    touch.get_multi_touch(&mut i2c).unwrap();
+    // the same frame can be read again
+    touch.get_touch(&mut i2c).unwrap();
+
+    assert!(!i2c
+        .log
+        .iter()
+        .any(|op| matches!(op, Op::Write { reg, .. } if *reg == STATUS_REG)));
+    assert!(!touch.last_cleared());
+
+    touch.clear_status(&mut i2c).unwrap();
+    assert_eq!(i2c.get(STATUS_REG), 0);
+    assert!(touch.last_cleared());
+}
+
+#[test]
+fn read_touches_with_sorts_and_skips_excluded_points() {
+    static ZONES: [ExclusionZone; 1] = [ExclusionZone {
+        x: 0,
+        y: 0,
+        width: 10,
+        height: 480,
+    }];
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(4, 100, 200, 30), (1, 5, 100, 31), (2, 300, 400, 32)]);
+
+    let touch = Gt911Blocking::default()
+        .with_sorted_points(true)
+        .with_exclusion_zones(&ZONES);
+    let mut points = Vec::new();
+    let count = touch
+        .read_touches_with(&mut i2c, |track_id, x, y, area| {
+            points.push((track_id, x, y, area))
+        })
+        .unwrap();
+
+    assert_eq!(count, 2);
+    assert_eq!(points, [(2, 300, 400, 32), (4, 100, 200, 30)]);
+    assert_eq!(i2c.get(STATUS_REG), 0);
+    assert!(matches!(
+        touch.read_touches_with(&mut i2c, |_, _, _, _| panic!("no frame")),
+        Err(Error::NotReady)
+    ));
+}
+
+#[test]
+fn read_touches_with_reverse_slots_async() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 100, 200, 30), (1, 300, 400, 31)]);
+
+    let touch = Gt911::default().with_reverse_slots(true);
+    let mut buf = [0u8; GET_MULTITOUCH_BUF_SIZE];
+    let mut points = Vec::new();
+    let count = block_on(
+        touch.read_touches_with(&mut i2c, &mut buf, |track_id, x, y, _| {
+            points.push((track_id, x, y))
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(count, 2);
+    assert_eq!(points, [(1, 300, 400), (0, 100, 200)]);
+    assert_eq!(i2c.get(STATUS_REG), 0);
+}
+
+#[test]
+fn read_all_slots_raw_reads_every_slot() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 100, 200, 30)]);
+    i2c.log.clear();
+
+    let touch = Gt911Blocking::default();
+    let mut buf = [0u8; ALL_SLOTS_LEN];
+    touch.read_all_slots_raw(&mut i2c, &mut buf).unwrap();
+
+    assert_eq!(
+        i2c.log,
+        [Op::Read {
+            reg: TOUCHPOINT_1_REG,
+            len: ALL_SLOTS_LEN
+        }]
+    );
+    assert_eq!(&buf[..4], &[0, 100, 0, 200]);
+    // the status register is left alone
+    assert_eq!(i2c.get(STATUS_REG), 0x81);
+}
+
+#[test]
+fn exclusion_zones_can_be_computed_at_runtime() {
+    let (width, height) = (800u16, 480u16);
+    // a 10 pixel bezel on the right and bottom edges of whatever the panel reports
+    let zones = [
+        ExclusionZone {
+            x: width - 10,
+            y: 0,
+            width: 10,
+            height,
+        },
+        ExclusionZone {
+            x: 0,
+            y: height - 10,
+            width,
+            height: 10,
+        },
+    ];
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 795, 100, 1), (1, 400, 475, 1), (2, 400, 200, 1)]);
+
+    let touch = Gt911::default().with_exclusion_zones(&zones);
+    let mut buf = [0u8; GET_MULTITOUCH_BUF_SIZE];
+    let points = block_on(touch.get_multi_touch(&mut i2c, &mut buf)).unwrap();
+
+    assert_eq!(points.len(), 1);
+    assert_eq!((points[0].x, points[0].y), (400, 200));
+}
+
+#[test]
+#[should_panic(expected = "Too many exclusion zones")]
+fn too_many_exclusion_zones_panics() {
+    let zone = ExclusionZone {
+        x: 0,
+        y: 0,
+        width: 1,
+        height: 1,
+    };
+    let _ =
+        Gt911Blocking::<MockI2c>::default().with_exclusion_zones(&[zone; MAX_EXCLUSION_ZONES + 1]);
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::{block_on, poll_once, FallingInt, IdleInt, MockI2c};
+use gt911::{Gt911, ReleaseOutcome, GET_MULTITOUCH_BUF_SIZE, GET_TOUCH_BUF_SIZE};
+
+#[test]
+fn count_change_skips_move_only_frames() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 10, 10, 1)]);
+    i2c.frames.push_back(vec![(0, 20, 20, 1)]);
+    i2c.frames.push_back(vec![(0, 30, 30, 1), (1, 40, 40, 1)]);
+    i2c.frames.push_back(vec![]);
+
+    let touch = Gt911::default();
+    let mut buf = [0u8; 1];
+    let mut count = 0;
+
+    // pressed
+    let result =
+        block_on(touch.wait_for_count_change(&mut i2c, &mut buf, &mut IdleInt, &mut count));
+    assert_eq!(result.unwrap(), 1);
+    // the move is skipped, the second finger is reported
+    let result =
+        block_on(touch.wait_for_count_change(&mut i2c, &mut buf, &mut IdleInt, &mut count));
+    assert_eq!(result.unwrap(), 2);
+    // both lifted
+    let result =
+        block_on(touch.wait_for_count_change(&mut i2c, &mut buf, &mut IdleInt, &mut count));
+    assert_eq!(result.unwrap(), 0);
+    assert_eq!(count, 0);
+
+    // no more frames, waits on INT
+    let result =
+        poll_once(touch.wait_for_count_change(&mut i2c, &mut buf, &mut IdleInt, &mut count));
+    assert!(result.is_none());
+}
+
+#[test]
+fn wait_for_touch_returns_a_pending_frame_without_waiting() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(0, 10, 20, 1)]);
+
+    let touch = Gt911::default();
+    let mut buf = [0u8; GET_TOUCH_BUF_SIZE];
+    // IdleInt never falls so this only completes if no edge is awaited
+    let point = block_on(touch.wait_for_touch(&mut i2c, &mut buf, &mut IdleInt));
+    assert_eq!(
+        point.unwrap().map(|point| (point.x, point.y)),
+        Some((10, 20))
+    );
+
+    // nothing new, waits on INT
+    let result = poll_once(touch.wait_for_touch(&mut i2c, &mut buf, &mut IdleInt));
+    assert!(result.is_none());
+}
+
+#[test]
+fn wait_for_release_times_out_while_the_finger_is_down() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(3, 10, 20, 1)]);
+
+    let touch = Gt911::default();
+    let mut buf = [0u8; GET_MULTITOUCH_BUF_SIZE];
+    // the finger frame is read, then INT never falls again and the timeout fires
+    let outcome = block_on(touch.wait_for_release(
+        &mut i2c,
+        &mut buf,
+        &mut IdleInt,
+        3,
+        core::future::ready(()),
+    ));
+
+    assert_eq!(outcome.unwrap(), ReleaseOutcome::TimedOut);
+}
+
+#[test]
+fn wait_for_release_sees_the_finger_lifted() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(3, 10, 20, 1)]);
+    i2c.frames.push_back(vec![(4, 50, 50, 1)]);
+
+    let touch = Gt911::default();
+    let mut buf = [0u8; GET_MULTITOUCH_BUF_SIZE];
+    let outcome = block_on(touch.wait_for_release(
+        &mut i2c,
+        &mut buf,
+        &mut FallingInt,
+        3,
+        core::future::pending(),
+    ));
+
+    assert_eq!(outcome.unwrap(), ReleaseOutcome::Released);
+}
+
+#[test]
+fn wait_for_release_waits_on_int_with_auto_clear_off() {
+    let mut i2c = MockI2c::new();
+    i2c.set_frame(&[(3, 10, 20, 1)]);
+
+    // the held frame is never cleared so it is returned again on every read, only waiting on INT
+    // between reads gives the timeout a chance to fire
+    let touch = Gt911::default().with_auto_clear(false);
+    let mut buf = [0u8; GET_MULTITOUCH_BUF_SIZE];
+    let outcome = block_on(touch.wait_for_release(
+        &mut i2c,
+        &mut buf,
+        &mut IdleInt,
+        3,
+        core::future::ready(()),
+    ));
+
+    assert_eq!(outcome.unwrap(), ReleaseOutcome::TimedOut);
+    assert_eq!(i2c.get(0x814E), 0x81);
+}